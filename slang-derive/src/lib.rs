@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{Data, DeriveInput, Fields, Lit, Meta, Type, parse_macro_input};
+use syn::ext::IdentExt;
+use syn::{Attribute, Data, DeriveInput, Fields, Lit, Meta, Type, parse_macro_input};
 
 /// Generate code to extract a field value from a UserAttribute at the given index
 ///
@@ -9,7 +10,6 @@ fn generate_field_extraction(field_type: &Type, index: usize) -> proc_macro2::To
 	let index_u32 = index as u32;
 
 	// Check if it's a known type
-<<<<<<< HEAD
 	if let Type::Path(type_path) = field_type
 		&& let Some(segment) = type_path.path.segments.last()
 	{
@@ -58,9 +58,9 @@ fn generate_field_extraction(field_type: &Type, index: usize) -> proc_macro2::To
 	}
 }
 
-/// Extract the name attribute value from the derive input
-fn extract_name_attribute(input: &DeriveInput) -> Option<String> {
-	for attr in &input.attrs {
+/// Extract the `#[slang(name = "...")]` value from a list of attributes
+fn extract_name_attribute(attrs: &[Attribute]) -> Option<String> {
+	for attr in attrs {
 		if attr.path().is_ident("slang")
 			&& let Meta::List(meta_list) = &attr.meta
 		{
@@ -87,7 +87,7 @@ pub fn derive_slang_attribute(input: TokenStream) -> TokenStream {
 	let name = &input.ident;
 
 	// Extract the attribute name if provided, otherwise use struct name
-	let expected_name = extract_name_attribute(&input).unwrap_or_else(|| name.to_string());
+	let expected_name = extract_name_attribute(&input.attrs).unwrap_or_else(|| name.to_string());
 
 	let implementation = match &input.data {
 		Data::Struct(data) => match &data.fields {
@@ -179,178 +179,6 @@ pub fn derive_slang_attribute(input: TokenStream) -> TokenStream {
 							"Enum variants must have exactly one unnamed field (e.g., Variant(InnerType))",
 						)
 						.to_compile_error()
-||||||| 9e26678
-=======
-	if let Type::Path(type_path) = field_type {
-		if let Some(segment) = type_path.path.segments.last() {
-			let type_name = segment.ident.to_string();
-
-			// Handle String
-			if type_name == "String" {
-				return quote! {
-					attr.argument_value_string(#index_u32)
-						.ok_or_else(|| ::shader_slang::reflection::AttributeError::InvalidValue(
-							format!("Missing or invalid string value at argument {}", #index_u32)
-						))?
-						.to_string()
-				};
-			}
-
-			// Handle f32
-			if type_name == "f32" {
-				return quote! {
-					attr.argument_value_float(#index_u32)
-						.ok_or_else(|| ::shader_slang::reflection::AttributeError::InvalidValue(
-							format!("Missing or invalid f32 value at argument {}", #index_u32)
-						))?
-				};
-			}
-
-			// Handle i32
-			if type_name == "i32" {
-				return quote! {
-					attr.argument_value_int(#index_u32)
-						.ok_or_else(|| ::shader_slang::reflection::AttributeError::InvalidValue(
-							format!("Missing or invalid i32 value at argument {}", #index_u32)
-						))?
-				};
-			}
-
-			// Unsupported type
-			return quote! {
-				compile_error!("Only String, f32, and i32 types are supported in SlangAttribute")
-			};
-		}
-	}
-
-	// Fallback error
-	quote! {
-		compile_error!("Only String, f32, and i32 types are supported in SlangAttribute")
-	}
-}
-
-/// Extract the name attribute value from the derive input
-fn extract_name_attribute(input: &DeriveInput) -> Option<String> {
-	for attr in &input.attrs {
-		if attr.path().is_ident("slang") {
-			if let Meta::List(meta_list) = &attr.meta {
-				// Parse the tokens inside #[slang(...)]
-				let nested = meta_list.parse_args::<Meta>().ok()?;
-				if let Meta::NameValue(name_value) = nested {
-					if name_value.path.is_ident("name") {
-						// The value is an Expr, extract string literal from it
-						if let syn::Expr::Lit(expr_lit) = &name_value.value {
-							if let Lit::Str(lit_str) = &expr_lit.lit {
-								return Some(lit_str.value());
-							}
-						}
-					}
-				}
-			}
-		}
-	}
-	None
-}
-
-#[proc_macro_derive(SlangAttribute, attributes(slang))]
-pub fn derive_slang_attribute(input: TokenStream) -> TokenStream {
-	let input = parse_macro_input!(input as DeriveInput);
-	let name = &input.ident;
-
-	// Extract the attribute name if provided, otherwise use struct name
-	let expected_name = extract_name_attribute(&input).unwrap_or_else(|| name.to_string());
-
-	let implementation = match &input.data {
-		Data::Struct(data) => match &data.fields {
-			Fields::Named(fields) => {
-				let field_extractions: Vec<_> = fields
-					.named
-					.iter()
-					.enumerate()
-					.map(|(i, field)| {
-						let field_name = &field.ident;
-						let field_type = &field.ty;
-						let extraction = generate_field_extraction(field_type, i);
-
-						quote! {
-							#field_name: #extraction
-						}
-					})
-					.collect();
-
-				let expected_count = fields.named.len();
-
-				quote! {
-					impl ::shader_slang::reflection::SlangAttribute for #name {
-						fn from_user_attribute(
-							attr: &::shader_slang::reflection::UserAttribute
-						) -> Result<Self, ::shader_slang::reflection::AttributeError> {
-							// Validate attribute name (using override or struct name)
-							let attr_name = attr.name().ok_or_else(|| {
-								::shader_slang::reflection::AttributeError::InvalidValue(
-									"Attribute has no name".to_string()
-								)
-							})?;
-
-							if attr_name != #expected_name {
-								return Err(::shader_slang::reflection::AttributeError::InvalidValue(
-									format!("Expected attribute name '{}', got '{}'", #expected_name, attr_name)
-								));
-							}
-
-							// Validate argument count
-							let arg_count = attr.argument_count();
-							let expected = #expected_count as u32;
-
-							if arg_count < expected {
-								return Err(::shader_slang::reflection::AttributeError::InvalidValue(
-									format!("Expected at least {} arguments, got {}", expected, arg_count)
-								));
-							}
-
-							Ok(Self {
-								#(#field_extractions,)*
-							})
-						}
-					}
-				}
-			}
-			_ => {
-				return syn::Error::new_spanned(
-					&input.ident,
-					"SlangAttribute can only be derived for structs with named fields or enums with tuple variants",
-				)
-				.to_compile_error()
-				.into();
-			}
-		},
-		Data::Enum(data) => {
-			// For enums, discriminate based on attribute name
-			let variants: Vec<_> = data.variants.iter().collect();
-
-			// Generate match arms for each variant
-			let match_arms = variants.iter().map(|variant| {
-				let variant_name = &variant.ident;
-				let variant_name_str = variant_name.to_string();
-
-				match &variant.fields {
-					Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
-						// Single unnamed field - extract the inner type and delegate to it
-						let inner_type = &fields.unnamed.first().unwrap().ty;
-						quote! {
-							#variant_name_str => {
-								let inner = <#inner_type as ::shader_slang::reflection::SlangAttribute>::from_user_attribute(attr)?;
-								Ok(Self::#variant_name(inner))
-							}
-						}
-					}
-					_ => {
-						return syn::Error::new_spanned(
-							variant,
-							"Enum variants must have exactly one unnamed field (e.g., Variant(InnerType))",
-						)
-						.to_compile_error();
->>>>>>> 50dca330609a567586d01be2d6fa0eee565cbdb3
 					}
 				}
 			});
@@ -388,3 +216,64 @@ pub fn derive_slang_attribute(input: TokenStream) -> TokenStream {
 
 	TokenStream::from(implementation)
 }
+
+#[proc_macro_derive(ShaderLayout, attributes(slang))]
+pub fn derive_shader_layout(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	let name = &input.ident;
+	let name_str = name.to_string();
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+	let fields = match &input.data {
+		Data::Struct(data) => match &data.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => {
+				return syn::Error::new_spanned(
+					&input.ident,
+					"ShaderLayout can only be derived for structs with named fields",
+				)
+				.to_compile_error()
+				.into();
+			}
+		},
+		_ => {
+			return syn::Error::new_spanned(
+				&input.ident,
+				"ShaderLayout can only be derived for structs",
+			)
+			.to_compile_error()
+			.into();
+		}
+	};
+
+	let host_fields = fields.iter().map(|field| {
+		let field_name = field.ident.as_ref().unwrap();
+		let field_type = &field.ty;
+		// Use the shader-side name if provided, otherwise the Rust field name
+		let shader_name =
+			extract_name_attribute(&field.attrs).unwrap_or_else(|| field_name.unraw().to_string());
+
+		quote! {
+			::shader_slang::reflection::HostField {
+				name: #shader_name,
+				offset: ::core::mem::offset_of!(Self, #field_name),
+				size: ::core::mem::size_of::<#field_type>(),
+				ty: <#field_type as ::shader_slang::reflection::ShaderLayout>::host_type(),
+			}
+		}
+	});
+
+	let implementation = quote! {
+		impl #impl_generics ::shader_slang::reflection::ShaderLayout for #name #ty_generics #where_clause {
+			fn host_type() -> ::shader_slang::reflection::HostType {
+				::shader_slang::reflection::HostType::Struct {
+					name: #name_str,
+					size: ::core::mem::size_of::<Self>(),
+					fields: vec![#(#host_fields,)*],
+				}
+			}
+		}
+	};
+
+	TokenStream::from(implementation)
+}
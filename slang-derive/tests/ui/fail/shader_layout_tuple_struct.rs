@@ -0,0 +1,6 @@
+use slang_derive::ShaderLayout;
+
+#[derive(ShaderLayout)]
+struct ShouldFail(f32, f32);
+
+fn main() {}
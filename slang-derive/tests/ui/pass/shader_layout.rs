@@ -0,0 +1,21 @@
+use slang_derive::ShaderLayout;
+
+#[derive(ShaderLayout)]
+#[repr(C)]
+struct Light {
+	position: [f32; 3],
+	intensity: f32,
+	#[slang(name = "colorTint")]
+	color_tint: [f32; 4],
+	r#type: u32,
+	_pad0: [u8; 12],
+}
+
+#[derive(ShaderLayout)]
+#[repr(C)]
+struct Scene {
+	lights: [Light; 4],
+	view: [[f32; 4]; 4],
+}
+
+fn main() {}
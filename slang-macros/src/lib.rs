@@ -0,0 +1,247 @@
+//! Compile-time shader compilation for `shader-slang`.
+//!
+//! These macros live in their own crate because they compile shaders through `shader-slang`, which in turn depends on
+//! `slang-derive` for its derive macros.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, Span};
+use quote::quote;
+use shader_slang::{self as slang, CompileTarget, Downcast};
+use std::path::{Path, PathBuf};
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{Ident, Lit, LitStr, Token, braced, parse_macro_input};
+
+/// Compiles an entry point of a Slang file while the calling crate is compiled.
+///
+/// ```ignore
+/// static SHADER: &[u32] = slang_macros::include_slang!(
+///     "shaders/lighting.slang",
+///     entry = "main",
+///     target = "spirv",
+///     profile = "glsl_450",
+///     defines = { USE_SHADOWS = "1" },
+/// );
+/// ```
+///
+/// The path is relative to the directory of the calling crate's `Cargo.toml`, and is also used as a search path for
+/// imports. The entry point must be marked with a `[shader(...)]` attribute.
+///
+/// All arguments except the path are optional. `entry` defaults to `main` and `target` to `spirv`, and the profile
+/// defaults to the one Slang picks for the target. Targets are named as in
+/// [`shader_slang::target_from_name`], e.g. `spirv`, `dxil`, `hlsl`, `wgsl` or `metal`.
+///
+/// SPIR-V expands to a `&'static [u32]`, so it can be passed to APIs expecting words. All other targets expand to a
+/// `&'static [u8]`. Errors reported by Slang become compile errors, and the crate is rebuilt when the shader or any
+/// file it imports changes. Until `proc_macro::tracked_path` is stable, this works by expanding to an unused
+/// `include_bytes!` of every such file.
+#[proc_macro]
+pub fn include_slang(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as IncludeSlang);
+	match input.expand() {
+		Ok(tokens) => tokens.into(),
+		Err(error) => error.to_compile_error().into(),
+	}
+}
+
+struct IncludeSlang {
+	path: LitStr,
+	entry: Option<LitStr>,
+	target: Option<LitStr>,
+	profile: Option<LitStr>,
+	defines: Vec<(String, String)>,
+}
+
+impl Parse for IncludeSlang {
+	fn parse(input: ParseStream) -> syn::Result<Self> {
+		let mut result = Self {
+			path: input.parse()?,
+			entry: None,
+			target: None,
+			profile: None,
+			defines: Vec::new(),
+		};
+
+		while !input.is_empty() {
+			input.parse::<Token![,]>()?;
+			if input.is_empty() {
+				break;
+			}
+
+			let key = input.parse::<Ident>()?;
+			input.parse::<Token![=]>()?;
+			match key.to_string().as_str() {
+				"entry" => result.entry = Some(input.parse()?),
+				"target" => result.target = Some(input.parse()?),
+				"profile" => result.profile = Some(input.parse()?),
+				"defines" => result.defines = parse_defines(input)?,
+				_ => {
+					return Err(syn::Error::new(
+						key.span(),
+						"expected `entry`, `target`, `profile` or `defines`",
+					));
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
+
+/// Parses `{ NAME = "value", "OTHER" = 1 }`.
+fn parse_defines(input: ParseStream) -> syn::Result<Vec<(String, String)>> {
+	let content;
+	braced!(content in input);
+
+	let mut defines = Vec::new();
+	while !content.is_empty() {
+		let name = if content.peek(LitStr) {
+			content.parse::<LitStr>()?.value()
+		} else {
+			content.call(Ident::parse_any)?.to_string()
+		};
+		content.parse::<Token![=]>()?;
+		let value = match content.parse::<Lit>()? {
+			Lit::Str(s) => s.value(),
+			Lit::Int(i) => i.base10_digits().to_string(),
+			Lit::Float(f) => f.base10_digits().to_string(),
+			Lit::Bool(b) => (b.value as u8).to_string(),
+			other => return Err(syn::Error::new(other.span(), "expected a string or number")),
+		};
+		defines.push((name, value));
+
+		if !content.is_empty() {
+			content.parse::<Token![,]>()?;
+		}
+	}
+	Ok(defines)
+}
+
+impl IncludeSlang {
+	fn expand(&self) -> syn::Result<proc_macro2::TokenStream> {
+		let span = self.path.span();
+		let error = |message: String| syn::Error::new(span, message);
+
+		let manifest_dir = std::env::var_os("CARGO_MANIFEST_DIR")
+			.map(PathBuf::from)
+			.ok_or_else(|| error("`CARGO_MANIFEST_DIR` is not set".to_string()))?;
+		let path = manifest_dir.join(self.path.value());
+		let source = std::fs::read_to_string(&path)
+			.map_err(|e| error(format!("failed to read `{}`: {e}", path.display())))?;
+
+		let target = match &self.target {
+			Some(target) => parse_target(target)?,
+			None => CompileTarget::Spirv,
+		};
+		let entry = self
+			.entry
+			.as_ref()
+			.map_or("main".to_string(), LitStr::value);
+
+		let global_session = slang::GlobalSession::new()
+			.ok_or_else(|| error("failed to create a Slang global session".into()))?;
+
+		let mut options = slang::CompilerOptions::default();
+		for (name, value) in &self.defines {
+			options = options
+				.macro_define(name, value)
+				.map_err(|e| error(e.to_string()))?;
+		}
+
+		let session = slang::SessionConfig::default()
+			.target(target, self.profile.as_ref().map(LitStr::value).as_deref())
+			.search_path(path.parent().unwrap_or(&manifest_dir))
+			.search_path(&manifest_dir)
+			.create_session(&global_session, &options)
+			.map_err(|e| slang_error(span, e))?;
+
+		let module_name = path
+			.file_stem()
+			.unwrap_or_default()
+			.to_string_lossy()
+			.into_owned();
+		let module = session
+			.load_module_from_source_string(&module_name, &path.to_string_lossy(), &source)
+			.map_err(|e| slang_error(span, e))?;
+		let entry_point = module.find_entry_point_by_name(&entry).ok_or_else(|| {
+			error(format!(
+				"no entry point `{entry}` marked with `[shader(...)]`"
+			))
+		})?;
+
+		let program = session
+			.create_composite_component_type(&[
+				module.downcast().clone(),
+				entry_point.downcast().clone(),
+			])
+			.map_err(|e| slang_error(span, e))?;
+		let code = program
+			.link()
+			.and_then(|linked| linked.entry_point_code(0, 0))
+			.map_err(|e| slang_error(span, e))?;
+
+		// Referencing every file with `include_bytes!` makes Cargo rebuild the caller when one of them changes. This is a
+		// workaround until `proc_macro::tracked_path` is stable.
+		let dependencies = module
+			.dependency_file_paths()
+			.map(|dependency| absolute(&manifest_dir, dependency))
+			.filter(|dependency| dependency.is_file())
+			.map(|dependency| dependency.to_string_lossy().into_owned())
+			.collect::<Vec<_>>();
+
+		let code = code.as_slice();
+		let value = if matches!(target, CompileTarget::Spirv) {
+			if !code.len().is_multiple_of(4) {
+				return Err(error("SPIR-V output is not a whole number of words".into()));
+			}
+			let words = code
+				.chunks_exact(4)
+				.map(|word| Literal::u32_suffixed(u32::from_le_bytes(word.try_into().unwrap())));
+			quote! { &[#(#words),*] as &'static [u32] }
+		} else {
+			let bytes = Literal::byte_string(code);
+			quote! { #bytes as &'static [u8] }
+		};
+
+		Ok(quote! {{
+			#(const _: &[u8] = include_bytes!(#dependencies);)*
+			#value
+		}})
+	}
+}
+
+fn parse_target(target: &LitStr) -> syn::Result<CompileTarget> {
+	slang::target_from_name(&target.value()).ok_or_else(|| {
+		let names = slang::target_names()
+			.map(|name| format!("`{name}`"))
+			.collect::<Vec<_>>()
+			.join(", ");
+		syn::Error::new(target.span(), format!("expected one of {names}"))
+	})
+}
+
+/// Turns the diagnostics of a failed compilation into a single error pointing at the path literal.
+///
+/// Slang locations cannot be mapped to Rust spans, so the file and line of each diagnostic are only part of its
+/// message, and all diagnostics share one error rather than repeating the same span.
+fn slang_error(span: Span, error: slang::Error) -> syn::Error {
+	let messages = error
+		.diagnostics()
+		.iter()
+		.filter(|d| d.is_error())
+		.map(ToString::to_string)
+		.collect::<Vec<_>>();
+	if messages.is_empty() {
+		return syn::Error::new(span, error.to_string());
+	}
+	syn::Error::new(span, messages.join("\n"))
+}
+
+fn absolute(base: &Path, path: &str) -> PathBuf {
+	let path = Path::new(path);
+	if path.is_absolute() {
+		path.to_path_buf()
+	} else {
+		base.join(path)
+	}
+}
@@ -0,0 +1,22 @@
+use slang_macros::include_slang;
+
+static SPIRV: &[u32] = include_slang!("../shaders/test.slang", profile = "glsl_450");
+
+static HLSL: &[u8] = include_slang!(
+	"../shaders/test.slang",
+	entry = "main",
+	target = "hlsl",
+	defines = { UNUSED = "1" },
+);
+
+#[test]
+fn include_spirv() {
+	const SPIRV_MAGIC: u32 = 0x07230203;
+	assert_eq!(SPIRV[0], SPIRV_MAGIC);
+}
+
+#[test]
+fn include_source_target() {
+	let source = std::str::from_utf8(HLSL).unwrap();
+	assert!(source.contains("numthreads"));
+}
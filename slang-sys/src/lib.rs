@@ -24,6 +24,33 @@ pub struct IBlobVtable {
 	pub getBufferSize: unsafe extern "C" fn(*mut c_void) -> usize,
 }
 
+/// `SlangPathType` values as used by the file system interfaces.
+pub type SlangPathTypeIntegral = u32;
+pub const SLANG_PATH_TYPE_DIRECTORY: SlangPathTypeIntegral = 0;
+pub const SLANG_PATH_TYPE_FILE: SlangPathTypeIntegral = 1;
+
+pub type FileSystemContentsCallBack = unsafe extern "C" fn(pathType: SlangPathTypeIntegral, name: *const c_char, userData: *mut c_void);
+
+#[repr(C)]
+pub struct IFileSystemVtable {
+	pub _base: ICastableVtable,
+
+	pub loadFile: unsafe extern "C" fn(*mut c_void, path: *const c_char, outBlob: *mut *mut ISlangBlob) -> SlangResult,
+}
+
+#[repr(C)]
+pub struct IFileSystemExtVtable {
+	pub _base: IFileSystemVtable,
+
+	pub getFileUniqueIdentity: unsafe extern "C" fn(*mut c_void, path: *const c_char, outUniqueIdentity: *mut *mut ISlangBlob) -> SlangResult,
+	pub calcCombinedPath: unsafe extern "C" fn(*mut c_void, fromPathType: SlangPathTypeIntegral, fromPath: *const c_char, path: *const c_char, pathOut: *mut *mut ISlangBlob) -> SlangResult,
+	pub getPathType: unsafe extern "C" fn(*mut c_void, path: *const c_char, pathTypeOut: *mut SlangPathTypeIntegral) -> SlangResult,
+	pub getPath: unsafe extern "C" fn(*mut c_void, kind: c_int, path: *const c_char, outPath: *mut *mut ISlangBlob) -> SlangResult,
+	pub clearCache: unsafe extern "C" fn(*mut c_void),
+	pub enumeratePathContents: unsafe extern "C" fn(*mut c_void, path: *const c_char, callback: FileSystemContentsCallBack, userData: *mut c_void) -> SlangResult,
+	pub getOSPathKind: unsafe extern "C" fn(*mut c_void) -> u8,
+}
+
 #[repr(C)]
 pub struct IGlobalSessionVtable {
 	pub _base: ISlangUnknown__bindgen_vtable,
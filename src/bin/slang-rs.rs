@@ -0,0 +1,343 @@
+//! Command-line access to the compiler, using the same API and defaults as applications built on this crate.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use shader_slang::{self as slang, CompileTarget, Downcast, OptimizationLevel};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(
+	name = "slang-rs",
+	version,
+	about = "Compile and inspect Slang shaders"
+)]
+struct Cli {
+	#[command(subcommand)]
+	command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+	/// Compile entry points to one or more targets.
+	Compile {
+		#[command(flatten)]
+		input: Input,
+
+		/// Entry points to compile. All entry points marked with `[shader(...)]` are compiled by default.
+		#[arg(short, long = "entry")]
+		entry_points: Vec<String>,
+
+		/// Output file for a single artifact, or directory for several. Files in the directory are named
+		/// `<entry>.<extension>`, or `<entry>.<target index>.<extension>` when there are several targets.
+		#[arg(short, long)]
+		output: PathBuf,
+	},
+
+	/// Print the reflection data of the linked program.
+	Reflect {
+		#[command(flatten)]
+		input: Input,
+
+		/// Print the JSON format of `slangc -reflection-json` instead of text.
+		#[arg(long)]
+		json: bool,
+	},
+
+	/// Print every file the module depends on, one per line.
+	Deps {
+		#[command(flatten)]
+		input: Input,
+	},
+
+	/// Write the serialized IR of the module.
+	Serialize {
+		#[command(flatten)]
+		input: Input,
+
+		#[arg(short, long)]
+		output: PathBuf,
+	},
+}
+
+#[derive(Args)]
+struct Input {
+	/// The `.slang` file to load.
+	file: PathBuf,
+
+	/// Targets as `<format>` or `<format>:<profile>`, e.g. `spirv:spirv_1_5`. Defaults to `spirv`.
+	#[arg(short, long = "target")]
+	targets: Vec<String>,
+
+	/// Additional search paths for imports.
+	#[arg(short = 'I', long = "include")]
+	include_paths: Vec<PathBuf>,
+
+	/// Preprocessor definitions as `NAME` or `NAME=VALUE`.
+	#[arg(short = 'D', long = "define")]
+	defines: Vec<String>,
+
+	#[arg(short = 'O', long, value_enum, default_value_t = Optimization::Default)]
+	optimization: Optimization,
+
+	/// Use row-major matrix layout instead of column-major.
+	#[arg(long)]
+	matrix_layout_row: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Optimization {
+	None,
+	Default,
+	High,
+	Maximal,
+}
+
+impl From<Optimization> for OptimizationLevel {
+	fn from(level: Optimization) -> Self {
+		match level {
+			Optimization::None => OptimizationLevel::None,
+			Optimization::Default => OptimizationLevel::Default,
+			Optimization::High => OptimizationLevel::High,
+			Optimization::Maximal => OptimizationLevel::Maximal,
+		}
+	}
+}
+
+fn main() -> ExitCode {
+	match run(Cli::parse().command) {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(error) => {
+			eprintln!("error: {error}");
+			ExitCode::FAILURE
+		}
+	}
+}
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+fn run(command: Command) -> Result<()> {
+	match command {
+		Command::Compile {
+			input,
+			entry_points,
+			output,
+		} => compile(&input, &entry_points, &output),
+		Command::Reflect { input, json } => reflect(&input, json),
+		Command::Deps { input } => {
+			let (_, module) = input.load(&global_session()?)?;
+			for path in module.dependency_file_paths() {
+				println!("{path}");
+			}
+			Ok(())
+		}
+		Command::Serialize { input, output } => {
+			let (_, module) = input.load(&global_session()?)?;
+			module.write_to_file(&output)?;
+			Ok(())
+		}
+	}
+}
+
+fn global_session() -> Result<slang::GlobalSession> {
+	Ok(slang::GlobalSession::new().ok_or("failed to create a Slang global session")?)
+}
+
+fn compile(input: &Input, names: &[String], output: &Path) -> Result<()> {
+	let global_session = global_session()?;
+	let (session, module) = input.load(&global_session)?;
+
+	let entry_points = if names.is_empty() {
+		module.entry_points().collect::<Vec<_>>()
+	} else {
+		names
+			.iter()
+			.map(|name| {
+				module
+					.find_entry_point_by_name(name)
+					.ok_or_else(|| format!("no entry point `{name}` marked with `[shader(...)]`"))
+			})
+			.collect::<std::result::Result<Vec<_>, _>>()?
+	};
+	if entry_points.is_empty() {
+		return Err("the module has no entry points".into());
+	}
+
+	let program = link(&session, &module, &entry_points)?;
+	let targets = input.targets()?;
+	let single = entry_points.len() == 1 && targets.len() == 1;
+	if !single {
+		std::fs::create_dir_all(output)?;
+	}
+
+	for (index, entry_point) in entry_points.iter().enumerate() {
+		let name = entry_point.function_reflection().name().unwrap_or_default();
+		for (target_index, (target, _)) in targets.iter().enumerate() {
+			let code = program.entry_point_code(index as _, target_index as _)?;
+			print_warnings(&code);
+
+			let extension = slang::file_extension(*target);
+			let path = if single {
+				output.to_path_buf()
+			} else if targets.len() == 1 {
+				output.join(format!("{name}.{extension}"))
+			} else {
+				output.join(format!("{name}.{target_index}.{extension}"))
+			};
+			std::fs::write(&path, code.as_slice())?;
+		}
+	}
+	Ok(())
+}
+
+fn reflect(input: &Input, json: bool) -> Result<()> {
+	let global_session = global_session()?;
+	let (session, module) = input.load(&global_session)?;
+	let entry_points = module.entry_points().collect::<Vec<_>>();
+	let program = link(&session, &module, &entry_points)?;
+	let shader = program.layout(0)?;
+
+	if json {
+		println!("{}", shader.to_reflection_json());
+		return Ok(());
+	}
+
+	println!("parameters:");
+	for parameter in shader.parameters() {
+		print_variable(parameter, 1);
+	}
+	for entry_point in shader.entry_points() {
+		println!(
+			"entry point {} ({:?}):",
+			entry_point.name().unwrap_or_default(),
+			entry_point.stage()
+		);
+		if entry_point.stage() == slang::Stage::Compute {
+			let [x, y, z] = entry_point.compute_thread_group_size();
+			println!("  thread group size: {x}x{y}x{z}");
+		}
+		for parameter in entry_point.parameters() {
+			print_variable(parameter, 1);
+		}
+		if let Some(result) = entry_point.result_var_layout() {
+			print_variable(result, 1);
+		}
+	}
+	Ok(())
+}
+
+fn print_variable(variable: &slang::reflection::VariableLayout, depth: usize) {
+	let indent = "  ".repeat(depth);
+	let type_layout = variable.type_layout();
+	let type_name = type_layout.and_then(|t| t.name()).unwrap_or_default();
+	let locations = variable
+		.categories()
+		.map(|category| {
+			let space = variable.binding_space_with_category(category);
+			let size = type_layout.map_or(0, |t| t.size(category));
+			format!(
+				"{category:?} {} space {space} size {size}",
+				variable.offset(category)
+			)
+		})
+		.collect::<Vec<_>>()
+		.join(", ");
+	let semantic = variable
+		.semantic_name()
+		.map(|name| format!(" : {name}{}", variable.semantic_index()))
+		.unwrap_or_default();
+
+	println!(
+		"{indent}{}: {type_name}{semantic} [{locations}]",
+		variable.name().unwrap_or("<unnamed>")
+	);
+	if let Some(type_layout) = type_layout
+		&& type_layout.kind() == slang::TypeKind::Struct
+	{
+		for field in type_layout.fields() {
+			print_variable(field, depth + 1);
+		}
+	}
+}
+
+impl Input {
+	fn targets(&self) -> Result<Vec<(CompileTarget, Option<&str>)>> {
+		if self.targets.is_empty() {
+			return Ok(vec![(CompileTarget::Spirv, None)]);
+		}
+		self.targets
+			.iter()
+			.map(|target| {
+				let (format, profile) = match target.split_once(':') {
+					Some((format, profile)) => (format, Some(profile)),
+					None => (target.as_str(), None),
+				};
+				Ok((parse_target(format)?, profile))
+			})
+			.collect()
+	}
+
+	/// Creates a session for the requested targets and loads the input file as a module.
+	fn load(
+		&self,
+		global_session: &slang::GlobalSession,
+	) -> Result<(slang::Session, slang::Module)> {
+		let mut options = slang::CompilerOptions::default()
+			.optimization(self.optimization.into())
+			.matrix_layout_row(self.matrix_layout_row);
+		for define in &self.defines {
+			let (name, value) = define.split_once('=').unwrap_or((define, "1"));
+			options = options.macro_define(name, value)?;
+		}
+
+		let mut config = slang::SessionConfig::default()
+			.search_path(self.file.parent().unwrap_or(Path::new(".")));
+		for (format, profile) in self.targets()? {
+			config = config.target(format, profile);
+		}
+		for path in &self.include_paths {
+			config = config.search_path(path);
+		}
+		let session = config.create_session(global_session, &options)?;
+
+		let source = std::fs::read_to_string(&self.file)?;
+		let name = self
+			.file
+			.file_stem()
+			.unwrap_or_default()
+			.to_string_lossy()
+			.into_owned();
+		let module =
+			session.load_module_from_source_string(&name, &self.file.to_string_lossy(), &source)?;
+		print_warnings(&module);
+
+		Ok((session, module.into_value()))
+	}
+}
+
+fn link(
+	session: &slang::Session,
+	module: &slang::Module,
+	entry_points: &[slang::EntryPoint],
+) -> Result<slang::ComponentType> {
+	let mut components = vec![module.downcast().clone()];
+	components.extend(entry_points.iter().map(|e| e.downcast().clone()));
+
+	let program = session.create_composite_component_type(&components)?;
+	print_warnings(&program);
+	let linked_program = program.link()?;
+	print_warnings(&linked_program);
+	Ok(linked_program.into_value())
+}
+
+fn print_warnings<T>(compiled: &slang::Compiled<T>) {
+	for warning in compiled.warnings() {
+		eprintln!("{warning}");
+	}
+}
+
+fn parse_target(name: &str) -> Result<CompileTarget> {
+	slang::target_from_name(name).ok_or_else(|| {
+		let names = slang::target_names().collect::<Vec<_>>().join(", ");
+		format!("unknown target `{name}`, expected one of {names}").into()
+	})
+}
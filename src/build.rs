@@ -0,0 +1,382 @@
+//! Compiling shaders from a build script.
+//!
+//! ```ignore
+//! // build.rs
+//! fn main() {
+//!     shader_slang::build::Build::new()
+//!         .directory("shaders")
+//!         .target(shader_slang::CompileTarget::Spirv, "spirv_1_5")
+//!         .compile()
+//!         .unwrap();
+//! }
+//!
+//! // src/lib.rs
+//! mod shaders {
+//!     include!(concat!(env!("OUT_DIR"), "/shaders.rs"));
+//! }
+//!
+//! let spirv: &[u8] = shaders::lighting::VERTEX_MAIN_SPIRV_SPIRV_1_5;
+//! ```
+
+use crate::{CompileTarget, CompilerOptions, Downcast, GlobalSession, Module, SessionConfig};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BuildError {
+	#[error("`OUT_DIR` is not set, call `out_dir` when not running from a build script")]
+	MissingOutDir,
+
+	/// Also returned for unknown profile names.
+	#[error("failed to create a Slang session: {0}")]
+	Session(crate::Error),
+
+	#[error("target {0:?} with profile `{1}` is added twice")]
+	DuplicateTarget(CompileTarget, String),
+
+	#[error("`{first}` and `{second}` would both be generated as module `{name}`")]
+	DuplicateModule {
+		name: String,
+		first: PathBuf,
+		second: PathBuf,
+	},
+
+	#[error("{first} and {second} would both be generated as `{module}::{name}`")]
+	DuplicateConstant {
+		module: String,
+		name: String,
+		first: String,
+		second: String,
+	},
+
+	#[error("`{path}`: {source}")]
+	Io {
+		path: PathBuf,
+		source: std::io::Error,
+	},
+
+	#[error("`{path}`: {error}")]
+	Slang { path: PathBuf, error: crate::Error },
+}
+
+/// A compiled entry point written to the output directory.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+	pub module: String,
+	pub entry_point: String,
+	pub target: CompileTarget,
+	pub profile: String,
+	pub path: PathBuf,
+}
+
+/// Compiles every `.slang` file in a set of directories for a set of targets.
+///
+/// Each entry point is written to `OUT_DIR/slang/<module>/<entry point>.<target index>.<extension>`, and a Rust file
+/// with one `include_bytes!` constant per entry point and target, named after the entry point, format and profile such
+/// as `VERTEX_MAIN_SPIRV_SPIRV_1_5`, is generated next to it. Cargo is told to rerun the build
+/// script when a source directory or any file a module depends on changes, including transitive imports.
+pub struct Build {
+	directories: Vec<PathBuf>,
+	search_paths: Vec<PathBuf>,
+	targets: Vec<(CompileTarget, String)>,
+	options: CompilerOptions,
+	out_dir: Option<PathBuf>,
+	output_file: String,
+}
+
+impl Default for Build {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Build {
+	pub fn new() -> Self {
+		Self {
+			directories: Vec::new(),
+			search_paths: Vec::new(),
+			targets: Vec::new(),
+			options: CompilerOptions::default(),
+			out_dir: None,
+			output_file: "shaders.rs".to_string(),
+		}
+	}
+
+	/// Adds a directory whose `.slang` files are compiled. Subdirectories are not searched, but modules in them can
+	/// be imported.
+	pub fn directory(mut self, path: impl AsRef<Path>) -> Self {
+		self.directories.push(path.as_ref().to_path_buf());
+		self
+	}
+
+	/// Adds a path used to resolve imports. Source directories are always searched.
+	pub fn search_path(mut self, path: impl AsRef<Path>) -> Self {
+		self.search_paths.push(path.as_ref().to_path_buf());
+		self
+	}
+
+	/// Adds a target, given as a format and a profile name such as `spirv_1_5` or `sm_6_5`.
+	pub fn target(mut self, format: CompileTarget, profile: &str) -> Self {
+		self.targets.push((format, profile.to_string()));
+		self
+	}
+
+	/// Sets the compiler options used for all targets.
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Overrides the output directory, which defaults to `OUT_DIR`.
+	pub fn out_dir(mut self, path: impl AsRef<Path>) -> Self {
+		self.out_dir = Some(path.as_ref().to_path_buf());
+		self
+	}
+
+	/// Sets the name of the generated Rust file, `shaders.rs` by default.
+	pub fn output_file(mut self, name: &str) -> Self {
+		self.output_file = name.to_string();
+		self
+	}
+
+	pub fn compile(self) -> Result<Vec<Artifact>, BuildError> {
+		let out_dir = match &self.out_dir {
+			Some(out_dir) => out_dir.clone(),
+			None => std::env::var_os("OUT_DIR")
+				.map(PathBuf::from)
+				.ok_or(BuildError::MissingOutDir)?,
+		};
+
+		let mut config = SessionConfig::default();
+		for (index, (format, profile)) in self.targets.iter().enumerate() {
+			if self.targets[..index].contains(&(*format, profile.clone())) {
+				return Err(BuildError::DuplicateTarget(*format, profile.clone()));
+			}
+			config = config.target(*format, Some(profile));
+		}
+		for path in self.directories.iter().chain(&self.search_paths) {
+			config = config.search_path(path);
+		}
+
+		let global_session =
+			GlobalSession::new().ok_or(BuildError::Session(crate::Error::Code(crate::E_FAIL)))?;
+		let session = config
+			.create_session(&global_session, &self.options)
+			.map_err(BuildError::Session)?;
+
+		let mut artifacts = Vec::new();
+		let mut generated = String::from("// Generated by `shader_slang::build`.\n");
+		let mut modules = HashMap::<String, PathBuf>::new();
+		for directory in &self.directories {
+			println!("cargo:rerun-if-changed={}", directory.display());
+
+			for path in slang_files(directory)? {
+				let module_name = path
+					.file_stem()
+					.unwrap_or_default()
+					.to_string_lossy()
+					.into_owned();
+				// Slang cannot load two modules with the same name, and Rust cannot declare two modules with the same
+				// identifier.
+				let module_identifier = module_identifier(&module_name);
+				if let Some(first) = modules.insert(module_identifier.clone(), path.clone()) {
+					return Err(BuildError::DuplicateModule {
+						name: module_identifier,
+						first,
+						second: path,
+					});
+				}
+
+				let source = std::fs::read_to_string(&path).map_err(|source| BuildError::Io {
+					path: path.clone(),
+					source,
+				})?;
+				let slang_error = |error| BuildError::Slang {
+					path: path.clone(),
+					error,
+				};
+
+				let module = session
+					.load_module_from_source_string(&module_name, &path.to_string_lossy(), &source)
+					.map(warn)
+					.map_err(slang_error)?;
+				for dependency in module.dependency_file_paths() {
+					println!("cargo:rerun-if-changed={dependency}");
+				}
+
+				let module_artifacts =
+					self.compile_module(&session, &module, &path, &module_name, &out_dir)?;
+				if !module_artifacts.is_empty() {
+					write_module(&mut generated, &module_name, &module_artifacts)?;
+				}
+				artifacts.extend(module_artifacts);
+			}
+		}
+
+		let output_path = out_dir.join(&self.output_file);
+		std::fs::write(&output_path, generated).map_err(|source| BuildError::Io {
+			path: output_path,
+			source,
+		})?;
+		Ok(artifacts)
+	}
+
+	fn compile_module(
+		&self,
+		session: &crate::Session,
+		module: &Module,
+		path: &Path,
+		module_name: &str,
+		out_dir: &Path,
+	) -> Result<Vec<Artifact>, BuildError> {
+		let entry_points = module.entry_points().collect::<Vec<_>>();
+		if entry_points.is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let slang_error = |error| BuildError::Slang {
+			path: path.to_path_buf(),
+			error,
+		};
+		let io_error = |path: &Path| {
+			let path = path.to_path_buf();
+			move |source| BuildError::Io { path, source }
+		};
+
+		let mut components = vec![module.downcast().clone()];
+		components.extend(entry_points.iter().map(|e| e.downcast().clone()));
+		let program = session
+			.create_composite_component_type(&components)
+			.map(warn)
+			.map_err(slang_error)?;
+		let linked_program = program.link().map(warn).map_err(slang_error)?;
+
+		let module_dir = out_dir.join("slang").join(module_name);
+		std::fs::create_dir_all(&module_dir).map_err(io_error(&module_dir))?;
+
+		let mut artifacts = Vec::new();
+		for (index, entry_point) in entry_points.iter().enumerate() {
+			let name = entry_point
+				.function_reflection()
+				.name()
+				.unwrap_or_default()
+				.to_string();
+
+			for (target_index, (target, profile)) in self.targets.iter().enumerate() {
+				let code = linked_program
+					.entry_point_code(index as _, target_index as _)
+					.map(warn)
+					.map_err(slang_error)?;
+				let path = module_dir.join(format!(
+					"{name}.{target_index}.{}",
+					crate::file_extension(*target)
+				));
+				std::fs::write(&path, code.as_slice()).map_err(io_error(&path))?;
+
+				artifacts.push(Artifact {
+					module: module_name.to_string(),
+					entry_point: name.clone(),
+					target: *target,
+					profile: profile.clone(),
+					path,
+				});
+			}
+		}
+		Ok(artifacts)
+	}
+}
+
+/// Reports the warnings of a successful step to Cargo.
+fn warn<T>(compiled: crate::Compiled<T>) -> T {
+	for warning in compiled.warnings() {
+		println!("cargo:warning={}", warning.to_string().replace('\n', " "));
+	}
+	compiled.into_value()
+}
+
+fn slang_files(directory: &Path) -> Result<Vec<PathBuf>, BuildError> {
+	let io_error = |source| BuildError::Io {
+		path: directory.to_path_buf(),
+		source,
+	};
+
+	let mut files = Vec::new();
+	for entry in std::fs::read_dir(directory).map_err(io_error)? {
+		let path = entry.map_err(io_error)?.path();
+		if path.is_file() && path.extension().is_some_and(|e| e == "slang") {
+			files.push(path);
+		}
+	}
+	// Keep the generated file stable across runs.
+	files.sort();
+	Ok(files)
+}
+
+fn write_module(
+	out: &mut String,
+	module_name: &str,
+	artifacts: &[Artifact],
+) -> Result<(), BuildError> {
+	let module = module_identifier(module_name);
+	writeln!(out, "\npub mod {module} {{").unwrap();
+
+	let mut constants = HashMap::<String, &Artifact>::new();
+	for artifact in artifacts {
+		let name = format!(
+			"{}_{}_{}",
+			identifier(&artifact.entry_point).to_uppercase(),
+			identifier(&format!("{:?}", artifact.target)).to_uppercase(),
+			identifier(&artifact.profile).to_uppercase(),
+		);
+		if let Some(first) = constants.insert(name.clone(), artifact) {
+			let describe =
+				|a: &Artifact| format!("`{}` for {:?} `{}`", a.entry_point, a.target, a.profile);
+			return Err(BuildError::DuplicateConstant {
+				module,
+				name,
+				first: describe(first),
+				second: describe(artifact),
+			});
+		}
+
+		writeln!(
+			out,
+			"\tpub const {name}: &[u8] = include_bytes!({:?});",
+			artifact.path.to_string_lossy(),
+		)
+		.unwrap();
+	}
+	out.push_str("}\n");
+	Ok(())
+}
+
+/// The Rust module generated for a Slang module, such as `lighting` or `r#type`.
+fn module_identifier(module_name: &str) -> String {
+	crate::reflection::codegen::field_identifier(&identifier(module_name).to_lowercase())
+}
+
+/// Converts a name such as `vertexMain` or `my-shader` to `vertex_main` or `my_shader`, keeping the case of each
+/// character.
+fn identifier(name: &str) -> String {
+	let mut out = String::new();
+	let mut previous = None::<char>;
+	for c in name.chars() {
+		if !c.is_ascii_alphanumeric() {
+			out.push('_');
+		} else {
+			if c.is_ascii_uppercase()
+				&& previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+			{
+				out.push('_');
+			}
+			out.push(c);
+		}
+		previous = Some(c);
+	}
+	if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+		out.insert(0, '_');
+	}
+	out
+}
@@ -0,0 +1,267 @@
+//! A persistent, content-addressed cache of compiled entry points.
+//!
+//! ```ignore
+//! let cache = ShaderCache::new("target/shader-cache")?.max_size(64 << 20);
+//!
+//! // Loading and linking is still needed to compute the hash, but code generation is skipped on a hit.
+//! let entry = cache.entry_point_code(&global_session, &session_desc, &linked_program, 0, 0)?;
+//! let spirv = &entry.code;
+//! ```
+
+use crate::reflection::snapshot;
+use crate::{ComponentType, Error, GlobalSession, Result, SessionDesc};
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Identifies the output of one entry point for one target.
+///
+/// Built from the digest of the session description and the hash Slang computes for the entry point, which covers
+/// the sources of every module it depends on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey(String);
+
+impl CacheKey {
+	pub fn new(session_digest: &[u8], entry_point_hash: &[u8], target_index: i64) -> Self {
+		Self(format!(
+			"{}-{}-{target_index}",
+			hex(session_digest),
+			hex(entry_point_hash)
+		))
+	}
+
+	/// Computes the key of an entry point of `program`, which must have been created in a session described by
+	/// `session_desc`.
+	pub fn for_entry_point(
+		global_session: &GlobalSession,
+		session_desc: &SessionDesc,
+		program: &ComponentType,
+		entry_point_index: i64,
+		target_index: i64,
+	) -> Result<Self> {
+		let digest = global_session.session_desc_digest(session_desc)?;
+		let hash = program
+			.entry_point_hash(entry_point_index, target_index)
+			.ok_or(Error::Code(crate::E_FAIL))?;
+		Ok(Self::new(digest.as_slice(), hash.as_slice(), target_index))
+	}
+
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+/// A cached entry point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+	pub code: Vec<u8>,
+	/// Reflection data of the program for the target, if it was stored.
+	pub reflection: Option<snapshot::Shader>,
+}
+
+/// Stores compiled code and reflection data in a directory, evicting the least recently used entries once the
+/// directory grows beyond a size limit.
+///
+/// Each entry is written to a temporary file and renamed into place, so several processes can share a directory.
+/// Unreadable entries are treated as misses.
+pub struct ShaderCache {
+	dir: PathBuf,
+	max_size: u64,
+}
+
+impl ShaderCache {
+	/// Opens or creates a cache in `dir`, with a size limit of 256 MiB.
+	pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+		let dir = dir.into();
+		fs::create_dir_all(&dir)?;
+		Ok(Self {
+			dir,
+			max_size: 256 << 20,
+		})
+	}
+
+	/// Sets the size in bytes above which entries are evicted.
+	pub fn max_size(mut self, bytes: u64) -> Self {
+		self.max_size = bytes;
+		self
+	}
+
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	pub fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+		let code_path = self.path(key, CODE_EXTENSION);
+		let code = fs::read(&code_path).ok()?;
+		let reflection = match fs::read_to_string(self.path(key, REFLECTION_EXTENSION)) {
+			Ok(json) => Some(snapshot::Shader::from_reflection_json(&json).ok()?),
+			Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+			Err(_) => return None,
+		};
+
+		// The modification time of the code file doubles as the last access time for eviction.
+		let _ = File::options()
+			.write(true)
+			.open(&code_path)
+			.and_then(|file| file.set_modified(SystemTime::now()));
+
+		Some(CacheEntry { code, reflection })
+	}
+
+	/// Stores an entry, replacing any previous one with the same key, and evicts entries if the cache is too large.
+	pub fn insert(
+		&self,
+		key: &CacheKey,
+		code: &[u8],
+		reflection: Option<&snapshot::Shader>,
+	) -> io::Result<()> {
+		// The code file is written last, since its presence marks the entry as complete.
+		match reflection {
+			Some(reflection) => self.write(
+				key,
+				REFLECTION_EXTENSION,
+				reflection.to_reflection_json().as_bytes(),
+			)?,
+			None => remove_if_exists(&self.path(key, REFLECTION_EXTENSION))?,
+		}
+		self.write(key, CODE_EXTENSION, code)?;
+		self.evict()
+	}
+
+	pub fn remove(&self, key: &CacheKey) -> io::Result<()> {
+		remove_if_exists(&self.path(key, CODE_EXTENSION))?;
+		remove_if_exists(&self.path(key, REFLECTION_EXTENSION))
+	}
+
+	/// Total size of all entries in bytes.
+	pub fn size(&self) -> io::Result<u64> {
+		Ok(self.entries()?.iter().map(|entry| entry.size).sum())
+	}
+
+	/// Removes the least recently used entries until the cache fits its size limit.
+	pub fn evict(&self) -> io::Result<()> {
+		let mut entries = self.entries()?;
+		let mut size = entries.iter().map(|entry| entry.size).sum::<u64>();
+		if size <= self.max_size {
+			return Ok(());
+		}
+
+		entries.sort_by_key(|entry| entry.accessed);
+		for entry in entries {
+			if size <= self.max_size {
+				break;
+			}
+			self.remove(&entry.key)?;
+			size -= entry.size;
+		}
+		Ok(())
+	}
+
+	/// Removes every entry.
+	pub fn clear(&self) -> io::Result<()> {
+		for entry in self.entries()? {
+			self.remove(&entry.key)?;
+		}
+		Ok(())
+	}
+
+	/// Returns the cached code and reflection data of an entry point, compiling and storing it on a miss.
+	///
+	/// `program` must be linked and have been created in a session described by `session_desc`. Failing to write the
+	/// cache does not fail the compilation.
+	pub fn entry_point_code(
+		&self,
+		global_session: &GlobalSession,
+		session_desc: &SessionDesc,
+		program: &ComponentType,
+		entry_point_index: i64,
+		target_index: i64,
+	) -> Result<CacheEntry> {
+		let key = CacheKey::for_entry_point(
+			global_session,
+			session_desc,
+			program,
+			entry_point_index,
+			target_index,
+		)?;
+		if let Some(entry) = self.get(&key) {
+			return Ok(entry);
+		}
+
+		let code = program.entry_point_code(entry_point_index, target_index)?;
+		let reflection = program.layout(target_index)?.snapshot();
+		let _ = self.insert(&key, code.as_slice(), Some(&reflection));
+
+		Ok(CacheEntry {
+			code: code.as_slice().to_vec(),
+			reflection: Some(reflection),
+		})
+	}
+
+	fn path(&self, key: &CacheKey, extension: &str) -> PathBuf {
+		self.dir.join(format!("{}.{extension}", key.0))
+	}
+
+	fn write(&self, key: &CacheKey, extension: &str, contents: &[u8]) -> io::Result<()> {
+		let temporary = self
+			.dir
+			.join(format!("{}.{extension}.{}.tmp", key.0, std::process::id()));
+		fs::write(&temporary, contents)?;
+		fs::rename(&temporary, self.path(key, extension)).inspect_err(|_| {
+			let _ = fs::remove_file(&temporary);
+		})
+	}
+
+	fn entries(&self) -> io::Result<Vec<StoredEntry>> {
+		let mut entries = Vec::new();
+		for file in fs::read_dir(&self.dir)? {
+			let path = file?.path();
+			if path.extension().is_none_or(|e| e != CODE_EXTENSION) {
+				continue;
+			}
+			let Some(key) = path.file_stem().and_then(|s| s.to_str()) else {
+				continue;
+			};
+			let key = CacheKey(key.to_string());
+
+			// Entries removed by another process in the meantime are skipped.
+			let Ok(metadata) = fs::metadata(&path) else {
+				continue;
+			};
+			let reflection_size =
+				fs::metadata(self.path(&key, REFLECTION_EXTENSION)).map_or(0, |m| m.len());
+			entries.push(StoredEntry {
+				size: metadata.len() + reflection_size,
+				accessed: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+				key,
+			});
+		}
+		Ok(entries)
+	}
+}
+
+const CODE_EXTENSION: &str = "bin";
+const REFLECTION_EXTENSION: &str = "json";
+
+struct StoredEntry {
+	key: CacheKey,
+	size: u64,
+	accessed: SystemTime,
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+	match fs::remove_file(path) {
+		Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+		_ => Ok(()),
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	let mut out = String::with_capacity(bytes.len() * 2);
+	for byte in bytes {
+		write!(out, "{byte:02x}").unwrap();
+	}
+	out
+}
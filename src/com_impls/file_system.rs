@@ -0,0 +1,683 @@
+//////
+//
+// Imports
+//
+
+// Standard library
+use std::{
+	collections::BTreeMap,
+	ffi::{CStr, c_char, c_int, c_void},
+	io,
+	path::PathBuf,
+	sync::atomic::{AtomicU32, Ordering},
+};
+
+// Local imports
+use crate::{com_impls::*, *};
+
+//////
+//
+// Enums
+//
+
+/// The kind of object a path refers to in a [`FileSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathType {
+	Directory,
+	File,
+}
+
+//////
+//
+// Traits
+//
+
+/// A file system that *Slang* can use to resolve `import`s and `#include`s, see
+/// [`SessionDesc::file_system`](crate::SessionDesc::file_system).
+///
+/// Paths are passed in exactly as *Slang* constructed them, i.e. search paths combined with module names using `/` as
+/// the separator. Use [`normalize_path`] to obtain a canonical form for lookups.
+pub trait FileSystem: Send + Sync {
+	/// Returns the full contents of the file at `path`.
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>>;
+
+	/// Reports whether `path` names a file or a directory. Must fail with [`io::ErrorKind::NotFound`] if it names
+	/// neither.
+	fn path_type(&self, path: &str) -> io::Result<PathType>;
+
+	/// Returns a string that is identical for all paths referring to the same file. *Slang* uses this to avoid loading
+	/// a module twice.
+	fn unique_identity(&self, path: &str) -> io::Result<String> {
+		self.path_type(path)?;
+		Ok(normalize_path(path))
+	}
+
+	/// Lists the names and types of the direct children of the directory at `path`.
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		let _ = path;
+		Err(io::ErrorKind::Unsupported.into())
+	}
+}
+
+impl<T: FileSystem + ?Sized> FileSystem for Box<T> {
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>> {
+		(**self).load_file(path)
+	}
+
+	fn path_type(&self, path: &str) -> io::Result<PathType> {
+		(**self).path_type(path)
+	}
+
+	fn unique_identity(&self, path: &str) -> io::Result<String> {
+		(**self).unique_identity(path)
+	}
+
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		(**self).read_dir(path)
+	}
+}
+
+impl<T: FileSystem + ?Sized> FileSystem for std::sync::Arc<T> {
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>> {
+		(**self).load_file(path)
+	}
+
+	fn path_type(&self, path: &str) -> io::Result<PathType> {
+		(**self).path_type(path)
+	}
+
+	fn unique_identity(&self, path: &str) -> io::Result<String> {
+		(**self).unique_identity(path)
+	}
+
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		(**self).read_dir(path)
+	}
+}
+
+//////
+//
+// Structs
+//
+
+/// A [`FileSystem`] that serves files from memory, e.g. shader sources embedded into the binary via
+/// [`include_str!`]. Directories exist implicitly for every prefix of a stored path.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFileSystem {
+	files: BTreeMap<String, Vec<u8>>,
+}
+impl MemoryFileSystem {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds a file, replacing any previous file at the same path.
+	pub fn insert(&mut self, path: &str, contents: impl Into<Vec<u8>>) {
+		self.files.insert(normalize_path(path), contents.into());
+	}
+
+	/// Builder-style variant of [`insert`](Self::insert).
+	pub fn with_file(mut self, path: &str, contents: impl Into<Vec<u8>>) -> Self {
+		self.insert(path, contents);
+		self
+	}
+
+	pub fn remove(&mut self, path: &str) -> Option<Vec<u8>> {
+		self.files.remove(&normalize_path(path))
+	}
+
+	pub fn paths(&self) -> impl Iterator<Item = &str> {
+		self.files.keys().map(String::as_str)
+	}
+
+	/// Iterates over all stored paths below the directory `dir`, with `dir/` stripped off.
+	fn children<'a>(&'a self, dir: &str) -> impl Iterator<Item = &'a str> {
+		let prefix = if dir.is_empty() {
+			String::new()
+		} else {
+			format!("{dir}/")
+		};
+		let prefix_len = prefix.len();
+		self.files
+			.range(prefix.clone()..)
+			.map(|(path, _)| path.as_str())
+			.take_while(move |path| path.starts_with(&prefix))
+			.map(move |path| &path[prefix_len..])
+	}
+}
+impl FileSystem for MemoryFileSystem {
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>> {
+		self.files
+			.get(&normalize_path(path))
+			.cloned()
+			.ok_or_else(|| io::ErrorKind::NotFound.into())
+	}
+
+	fn path_type(&self, path: &str) -> io::Result<PathType> {
+		let path = normalize_path(path);
+		if self.files.contains_key(&path) {
+			Ok(PathType::File)
+		} else if self.children(&path).next().is_some() {
+			Ok(PathType::Directory)
+		} else {
+			Err(io::ErrorKind::NotFound.into())
+		}
+	}
+
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		let mut entries: Vec<(PathType, String)> = Vec::new();
+		for child in self.children(&normalize_path(path)) {
+			let entry = match child.split_once('/') {
+				Some((dir, _)) => (PathType::Directory, dir),
+				None => (PathType::File, child),
+			};
+			if entries.last().is_none_or(|(_, last)| last != entry.1) {
+				entries.push((entry.0, entry.1.to_string()));
+			}
+		}
+		Ok(entries)
+	}
+}
+
+/// A [`FileSystem`] that resolves every path against `upper` first and falls back to `lower` for paths `upper` does
+/// not know about. Directory listings are merged, with entries of `upper` taking precedence.
+#[derive(Debug, Clone, Default)]
+pub struct OverlayFileSystem<Upper, Lower> {
+	pub upper: Upper,
+	pub lower: Lower,
+}
+impl<Upper: FileSystem, Lower: FileSystem> OverlayFileSystem<Upper, Lower> {
+	pub fn new(upper: Upper, lower: Lower) -> Self {
+		Self { upper, lower }
+	}
+}
+impl<Upper: FileSystem, Lower: FileSystem> FileSystem for OverlayFileSystem<Upper, Lower> {
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>> {
+		match self.upper.load_file(path) {
+			Err(err) if err.kind() == io::ErrorKind::NotFound => self.lower.load_file(path),
+			result => result,
+		}
+	}
+
+	fn path_type(&self, path: &str) -> io::Result<PathType> {
+		match self.upper.path_type(path) {
+			Err(err) if err.kind() == io::ErrorKind::NotFound => self.lower.path_type(path),
+			result => result,
+		}
+	}
+
+	fn unique_identity(&self, path: &str) -> io::Result<String> {
+		match self.upper.unique_identity(path) {
+			Err(err) if err.kind() == io::ErrorKind::NotFound => self.lower.unique_identity(path),
+			result => result,
+		}
+	}
+
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		let mut entries = match self.upper.read_dir(path) {
+			Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+			result => result?,
+		};
+		match self.lower.read_dir(path) {
+			Ok(lower) => {
+				for entry in lower {
+					if !entries.iter().any(|(_, name)| *name == entry.1) {
+						entries.push(entry);
+					}
+				}
+			}
+			Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+			Err(err) => return Err(err),
+		}
+		Ok(entries)
+	}
+}
+
+/// A [`FileSystem`] backed by the real file system, resolving relative paths against `root`. Mostly useful as the
+/// `lower` layer of an [`OverlayFileSystem`].
+#[derive(Debug, Clone, Default)]
+pub struct OsFileSystem {
+	pub root: PathBuf,
+}
+impl OsFileSystem {
+	pub fn new(root: impl Into<PathBuf>) -> Self {
+		Self { root: root.into() }
+	}
+
+	fn resolve(&self, path: &str) -> PathBuf {
+		self.root.join(path)
+	}
+}
+impl FileSystem for OsFileSystem {
+	fn load_file(&self, path: &str) -> io::Result<Vec<u8>> {
+		std::fs::read(self.resolve(path))
+	}
+
+	fn path_type(&self, path: &str) -> io::Result<PathType> {
+		let metadata = std::fs::metadata(self.resolve(path))?;
+		Ok(if metadata.is_dir() {
+			PathType::Directory
+		} else {
+			PathType::File
+		})
+	}
+
+	fn unique_identity(&self, path: &str) -> io::Result<String> {
+		Ok(std::fs::canonicalize(self.resolve(path))?
+			.to_string_lossy()
+			.into_owned())
+	}
+
+	fn read_dir(&self, path: &str) -> io::Result<Vec<(PathType, String)>> {
+		std::fs::read_dir(self.resolve(path))?
+			.map(|entry| {
+				let entry = entry?;
+				let path_type = if entry.file_type()?.is_dir() {
+					PathType::Directory
+				} else {
+					PathType::File
+				};
+				Ok((path_type, entry.file_name().to_string_lossy().into_owned()))
+			})
+			.collect()
+	}
+}
+
+/// A COM object implementing [`ISlangFileSystemExt`](sys::IFileSystemExtVtable) on top of a Rust [`FileSystem`], so
+/// that it can be handed to *Slang* via [`SessionDesc::file_system`](crate::SessionDesc::file_system).
+#[repr(C)]
+pub struct FileSystemAdapter {
+	/// The VTable binding the COM interface to our struct.
+	vtable_: *const sys::IFileSystemExtVtable,
+
+	/// We implement reference counting using *Rust* atomics.
+	ref_count: AtomicU32,
+
+	/// The file system all requests are forwarded to.
+	file_system: Box<dyn FileSystem>,
+}
+impl FileSystemAdapter {
+	/// Wraps `file_system` into a new COM object with a reference count of 1. The result is usually passed on to
+	/// [`ComPtr::new`].
+	pub fn from_file_system(file_system: impl FileSystem + 'static) -> *mut FileSystemAdapter {
+		// Allocate our object and hand ownership over to COM
+		let boxed = Box::new(FileSystemAdapter {
+			vtable_: &VTABLE,
+			ref_count: AtomicU32::new(1),
+			file_system: Box::new(file_system),
+		});
+		Box::into_raw(boxed)
+	}
+
+	/// The Rust file system wrapped by this adapter.
+	pub fn file_system(&self) -> &dyn FileSystem {
+		&*self.file_system
+	}
+
+	#[inline]
+	fn this<'a>(this: *mut sys::ISlangUnknown) -> &'a FileSystemAdapter {
+		// Safety: our object layout is compatible; the incoming pointer is one we created.
+		unsafe { &*(this as *const FileSystemAdapter) }
+	}
+
+	#[inline]
+	fn this_void<'a>(this: *mut c_void) -> &'a FileSystemAdapter {
+		unsafe { &*(this as *const FileSystemAdapter) }
+	}
+}
+unsafe impl Interface for FileSystemAdapter {
+	type Vtable = sys::IFileSystemExtVtable;
+	const IID: UUID = FILE_SYSTEM_EXT_IID;
+
+	#[inline(always)]
+	unsafe fn as_raw<T>(&self) -> *mut T {
+		self as *const Self as *mut T
+	}
+}
+
+//////
+//
+// Functions
+//
+
+/// Brings a path into the canonical form used by [`MemoryFileSystem`]: separators are turned into `/`, `.` segments
+/// are dropped and `..` segments are resolved where possible.
+pub fn normalize_path(path: &str) -> String {
+	let path = path.replace('\\', "/");
+	let absolute = path.starts_with('/');
+
+	let mut segments: Vec<&str> = Vec::new();
+	for segment in path.split('/') {
+		match segment {
+			"" | "." => {}
+			".." if segments.last().is_some_and(|last| *last != "..") => {
+				segments.pop();
+			}
+			".." if absolute => {}
+			segment => segments.push(segment),
+		}
+	}
+
+	let joined = segments.join("/");
+	if absolute {
+		format!("/{joined}")
+	} else {
+		joined
+	}
+}
+
+/// Resolves `path` relative to `from`, which is either a directory or a file whose containing directory is used.
+fn combine_paths(from_type: PathType, from: &str, path: &str) -> String {
+	let is_absolute =
+		path.starts_with('/') || path.starts_with('\\') || path.as_bytes().get(1) == Some(&b':');
+	if is_absolute {
+		return normalize_path(path);
+	}
+
+	let from = from.replace('\\', "/");
+	let base = match from_type {
+		PathType::Directory => from.as_str(),
+		PathType::File => from.rsplit_once('/').map_or("", |(dir, _)| dir),
+	};
+	if base.is_empty() {
+		normalize_path(path)
+	} else {
+		normalize_path(&format!("{base}/{path}"))
+	}
+}
+
+fn path_type_to_raw(path_type: PathType) -> sys::SlangPathTypeIntegral {
+	match path_type {
+		PathType::Directory => sys::SLANG_PATH_TYPE_DIRECTORY,
+		PathType::File => sys::SLANG_PATH_TYPE_FILE,
+	}
+}
+
+fn error_to_result(error: io::Error) -> sys::SlangResult {
+	match error.kind() {
+		io::ErrorKind::NotFound => E_NOT_FOUND,
+		io::ErrorKind::Unsupported => E_NOTIMPL,
+		_ => E_FAIL,
+	}
+}
+
+/// Converts an incoming C string, bailing out of the calling COM method with `E_INVALIDARG` if that is not possible.
+macro_rules! str_arg {
+	($ptr:expr) => {{
+		let ptr: *const c_char = $ptr;
+		if ptr.is_null() {
+			return E_INVALIDARG;
+		}
+		match unsafe { CStr::from_ptr(ptr) }.to_str() {
+			Ok(s) => s,
+			Err(_) => return E_INVALIDARG,
+		}
+	}};
+}
+
+/// Hands `data` to *Slang* as a newly created blob.
+fn write_blob(out_blob: *mut *mut sys::ISlangBlob, data: Vec<u8>) -> sys::SlangResult {
+	if out_blob.is_null() {
+		return E_INVALIDARG;
+	}
+	unsafe { *out_blob = VecBlob::from_vec(data) as *mut sys::ISlangBlob };
+	S_OK
+}
+
+//////
+//
+// COM endpoint implementations
+//
+
+const CASTABLE_IID: UUID = uuid(
+	0x87ede0e1,
+	0x4852,
+	0x44b0,
+	[0x8b, 0xf2, 0xcb, 0x31, 0x87, 0x4d, 0xe2, 0x39],
+);
+
+const FILE_SYSTEM_IID: UUID = uuid(
+	0x003a09fc,
+	0x3a4d,
+	0x4ba0,
+	[0xad, 0x60, 0x1f, 0xd8, 0x63, 0xa9, 0x15, 0xab],
+);
+
+const FILE_SYSTEM_EXT_IID: UUID = uuid(
+	0x5fb632d2,
+	0x979d,
+	0x4481,
+	[0x9f, 0xee, 0x66, 0x3c, 0x3f, 0x14, 0x49, 0xe1],
+);
+
+/// `SlangPathKind` values accepted by `getPath`.
+const PATH_KIND_SIMPLIFIED: c_int = 0;
+const PATH_KIND_CANONICAL: c_int = 1;
+const PATH_KIND_DISPLAY: c_int = 3;
+
+/// `OSPathKind::None`, i.e. paths of this file system cannot be handed to the operating system.
+const OS_PATH_KIND_NONE: u8 = 0;
+
+fn supports_interface(iid: &sys::SlangUUID) -> bool {
+	eq_guid(iid, &IUnknown::IID)
+		|| eq_guid(iid, &CASTABLE_IID)
+		|| eq_guid(iid, &FILE_SYSTEM_IID)
+		|| eq_guid(iid, &FILE_SYSTEM_EXT_IID)
+}
+
+////
+// Interface: IUnknown
+
+unsafe extern "C" fn query_interface(
+	this: *mut sys::ISlangUnknown,
+	uuid: *const sys::SlangUUID,
+	out_object: *mut *mut c_void,
+) -> sys::SlangResult {
+	if out_object.is_null() || uuid.is_null() {
+		return E_INVALIDARG;
+	}
+
+	if supports_interface(unsafe { &*uuid }) {
+		// All of the interfaces we implement share the same object and vtable
+		FileSystemAdapter::this(this)
+			.ref_count
+			.fetch_add(1, Ordering::Relaxed);
+		unsafe { *out_object = this as *mut c_void };
+		S_OK
+	} else {
+		unsafe { *out_object = std::ptr::null_mut() };
+		E_NOINTERFACE
+	}
+}
+
+unsafe extern "C" fn add_ref(this: *mut sys::ISlangUnknown) -> u32 {
+	let obj = FileSystemAdapter::this(this);
+	let prev = obj.ref_count.fetch_add(1, Ordering::Relaxed);
+	prev + 1
+}
+
+unsafe extern "C" fn release(this: *mut sys::ISlangUnknown) -> u32 {
+	let obj = FileSystemAdapter::this(this);
+	let prev = obj.ref_count.fetch_sub(1, Ordering::Release);
+	if prev == 1 {
+		// Acquire to synchronize with potential writers before drop
+		std::sync::atomic::fence(Ordering::Acquire);
+		let _ = unsafe {
+			// Safety: we own the Box, and the Box is the only reference to it.
+			Box::from_raw(this as *mut FileSystemAdapter)
+		};
+		0
+	} else {
+		prev - 1
+	}
+}
+
+////
+// Interface: ICastable
+
+unsafe extern "C" fn cast_as(this: *mut c_void, guid: *const sys::SlangUUID) -> *mut c_void {
+	// Unlike queryInterface, castAs does not hand out a new reference
+	if !guid.is_null() && supports_interface(unsafe { &*guid }) {
+		this
+	} else {
+		std::ptr::null_mut()
+	}
+}
+
+////
+// Interface: ISlangFileSystem
+
+unsafe extern "C" fn load_file(
+	this: *mut c_void,
+	path: *const c_char,
+	out_blob: *mut *mut sys::ISlangBlob,
+) -> sys::SlangResult {
+	let path = str_arg!(path);
+	match FileSystemAdapter::this_void(this)
+		.file_system
+		.load_file(path)
+	{
+		Ok(data) => write_blob(out_blob, data),
+		Err(err) => error_to_result(err),
+	}
+}
+
+////
+// Interface: ISlangFileSystemExt
+
+unsafe extern "C" fn get_file_unique_identity(
+	this: *mut c_void,
+	path: *const c_char,
+	out_unique_identity: *mut *mut sys::ISlangBlob,
+) -> sys::SlangResult {
+	let path = str_arg!(path);
+	match FileSystemAdapter::this_void(this)
+		.file_system
+		.unique_identity(path)
+	{
+		Ok(identity) => write_blob(out_unique_identity, identity.into_bytes()),
+		Err(err) => error_to_result(err),
+	}
+}
+
+unsafe extern "C" fn calc_combined_path(
+	_this: *mut c_void,
+	from_path_type: sys::SlangPathTypeIntegral,
+	from_path: *const c_char,
+	path: *const c_char,
+	path_out: *mut *mut sys::ISlangBlob,
+) -> sys::SlangResult {
+	let from_path = str_arg!(from_path);
+	let path = str_arg!(path);
+	let from_type = match from_path_type {
+		sys::SLANG_PATH_TYPE_DIRECTORY => PathType::Directory,
+		sys::SLANG_PATH_TYPE_FILE => PathType::File,
+		_ => return E_INVALIDARG,
+	};
+	write_blob(
+		path_out,
+		combine_paths(from_type, from_path, path).into_bytes(),
+	)
+}
+
+unsafe extern "C" fn get_path_type(
+	this: *mut c_void,
+	path: *const c_char,
+	path_type_out: *mut sys::SlangPathTypeIntegral,
+) -> sys::SlangResult {
+	let path = str_arg!(path);
+	if path_type_out.is_null() {
+		return E_INVALIDARG;
+	}
+	match FileSystemAdapter::this_void(this)
+		.file_system
+		.path_type(path)
+	{
+		Ok(path_type) => {
+			unsafe { *path_type_out = path_type_to_raw(path_type) };
+			S_OK
+		}
+		Err(err) => error_to_result(err),
+	}
+}
+
+unsafe extern "C" fn get_path(
+	this: *mut c_void,
+	kind: c_int,
+	path: *const c_char,
+	out_path: *mut *mut sys::ISlangBlob,
+) -> sys::SlangResult {
+	let path = str_arg!(path);
+	match kind {
+		PATH_KIND_SIMPLIFIED | PATH_KIND_DISPLAY => {
+			write_blob(out_path, normalize_path(path).into_bytes())
+		}
+		PATH_KIND_CANONICAL => {
+			match FileSystemAdapter::this_void(this)
+				.file_system
+				.unique_identity(path)
+			{
+				Ok(identity) => write_blob(out_path, identity.into_bytes()),
+				Err(err) => error_to_result(err),
+			}
+		}
+		// Operating system paths are not available in general
+		_ => E_NOTIMPL,
+	}
+}
+
+unsafe extern "C" fn clear_cache(_this: *mut c_void) {
+	// We do not cache anything on this side of the interface
+}
+
+unsafe extern "C" fn enumerate_path_contents(
+	this: *mut c_void,
+	path: *const c_char,
+	callback: sys::FileSystemContentsCallBack,
+	user_data: *mut c_void,
+) -> sys::SlangResult {
+	let path = str_arg!(path);
+	let entries = match FileSystemAdapter::this_void(this)
+		.file_system
+		.read_dir(path)
+	{
+		Ok(entries) => entries,
+		Err(err) => return error_to_result(err),
+	};
+
+	for (path_type, name) in entries {
+		let Ok(name) = CString::new(name) else {
+			continue;
+		};
+		unsafe { callback(path_type_to_raw(path_type), name.as_ptr(), user_data) };
+	}
+	S_OK
+}
+
+unsafe extern "C" fn get_os_path_kind(_this: *mut c_void) -> u8 {
+	OS_PATH_KIND_NONE
+}
+
+////
+// Interface binding
+
+static VTABLE: sys::IFileSystemExtVtable = sys::IFileSystemExtVtable {
+	_base: sys::IFileSystemVtable {
+		_base: sys::ICastableVtable {
+			_base: sys::ISlangUnknown__bindgen_vtable {
+				ISlangUnknown_queryInterface: query_interface,
+				ISlangUnknown_addRef: add_ref,
+				ISlangUnknown_release: release,
+			},
+			castAs: cast_as,
+		},
+		loadFile: load_file,
+	},
+	getFileUniqueIdentity: get_file_unique_identity,
+	calcCombinedPath: calc_combined_path,
+	getPathType: get_path_type,
+	getPath: get_path,
+	clearCache: clear_cache,
+	enumeratePathContents: enumerate_path_contents,
+	getOSPathKind: get_os_path_kind,
+};
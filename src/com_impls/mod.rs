@@ -5,6 +5,12 @@ mod blob;
 #[allow(unused_imports)]
 pub use blob::{ImplementsISlangBlob, VecBlob}; // re-export
 
+mod file_system;
+pub use file_system::{
+	FileSystem, FileSystemAdapter, MemoryFileSystem, OsFileSystem, OverlayFileSystem, PathType,
+	normalize_path,
+}; // re-export
+
 /// The `HRESULT` code for successful execution of a COM method.
 pub const S_OK: sys::SlangResult = sys::SLANG_OK as i32;
 
@@ -14,6 +20,12 @@ pub const E_INVALIDARG: sys::SlangResult = 0x8007005 as i32;
 /// The `HRESULT` code indicating that the requested interface is not supported.
 pub const E_NOINTERFACE: sys::SlangResult = 0x80004002 as u32 as i32;
 
+/// The `HRESULT` code indicating that a COM method is not implemented.
+pub const E_NOTIMPL: sys::SlangResult = 0x80004001_u32 as i32;
+
+/// The *Slang*-specific result code (`SLANG_E_NOT_FOUND`) indicating that a requested item, e.g. a file, does not exist.
+pub const E_NOT_FOUND: sys::SlangResult = 0x82000005_u32 as i32;
+
 pub struct ComPtr<T: crate::Interface>(ptr::NonNull<T>);
 
 impl<T: crate::Interface> ComPtr<T> {
@@ -0,0 +1,238 @@
+//! Structured access to the diagnostic output produced by the Slang compiler.
+//!
+//! Slang reports errors and warnings as a single block of text. The types in this module turn that text into a list
+//! of [`Diagnostic`]s, so tools do not need to pattern-match the raw output themselves.
+
+use std::fmt;
+
+/// How severe a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+	Note,
+	Warning,
+	Error,
+	Fatal,
+	Internal,
+}
+
+impl Severity {
+	/// The spelling Slang uses for this severity in its diagnostic output.
+	pub fn as_str(&self) -> &'static str {
+		match self {
+			Severity::Note => "note",
+			Severity::Warning => "warning",
+			Severity::Error => "error",
+			Severity::Fatal => "fatal error",
+			Severity::Internal => "internal error",
+		}
+	}
+
+	/// Returns `true` for severities that cause a compilation to fail.
+	pub fn is_error(&self) -> bool {
+		*self >= Severity::Error
+	}
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(self.as_str())
+	}
+}
+
+/// A location in a source file. Lines and columns are 1-based.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+	pub line: u32,
+	/// The first column covered by the span, if Slang reported one.
+	pub column: Option<u32>,
+	/// The column just past the end of the span, if Slang reported one.
+	pub end_column: Option<u32>,
+}
+
+/// A single message reported by the Slang compiler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// The numeric diagnostic code, e.g. `30015` for an undefined identifier. Notes usually carry no code.
+	pub code: Option<u32>,
+	/// The file the diagnostic refers to, exactly as Slang printed it.
+	pub path: Option<String>,
+	pub span: Option<Span>,
+	pub message: String,
+	/// Notes that Slang emitted directly after this diagnostic, e.g. "see declaration of ...".
+	pub notes: Vec<Diagnostic>,
+}
+
+impl Diagnostic {
+	pub fn is_error(&self) -> bool {
+		self.severity.is_error()
+	}
+
+	pub fn is_warning(&self) -> bool {
+		self.severity == Severity::Warning
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if let Some(path) = &self.path {
+			f.write_str(path)?;
+			match self.span {
+				Some(Span {
+					line,
+					column: Some(column),
+					..
+				}) => write!(f, "({line},{column})")?,
+				Some(Span { line, .. }) => write!(f, "({line})")?,
+				None => {}
+			}
+			f.write_str(": ")?;
+		}
+
+		f.write_str(self.severity.as_str())?;
+		if let Some(code) = self.code {
+			write!(f, " {code}")?;
+		}
+		write!(f, ": {}", self.message)?;
+
+		for note in &self.notes {
+			write!(f, "\n{note}")?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Parses the textual diagnostic output of Slang into a list of [`Diagnostic`]s.
+///
+/// Notes are attached to the diagnostic they follow. Source excerpts are consumed to compute column spans, any other
+/// line that is not recognized as a diagnostic is skipped.
+pub fn parse_diagnostics(text: &str) -> Vec<Diagnostic> {
+	let mut diagnostics: Vec<Diagnostic> = Vec::new();
+
+	for line in text.lines() {
+		if let Some(diagnostic) = parse_header(line) {
+			match diagnostics.last_mut() {
+				Some(previous)
+					if diagnostic.severity == Severity::Note
+						&& previous.severity != Severity::Note =>
+				{
+					previous.notes.push(diagnostic)
+				}
+				_ => diagnostics.push(diagnostic),
+			}
+		} else if let Some((column, end_column)) = parse_caret_line(line) {
+			let Some(last) = diagnostics.last_mut() else {
+				continue;
+			};
+			let last = if last.notes.is_empty() {
+				last
+			} else {
+				last.notes.last_mut().unwrap()
+			};
+			if let Some(span) = &mut last.span
+				&& span.column.is_none()
+			{
+				span.column = Some(column);
+				span.end_column = Some(end_column);
+			}
+		}
+	}
+
+	diagnostics
+}
+
+/// Recognizes lines of the form `path(line[,column]): severity [code]: message` as well as the location-less
+/// `severity [code]: message`.
+fn parse_header(line: &str) -> Option<Diagnostic> {
+	if let Some(diagnostic) = parse_severity_and_message(line) {
+		return Some(diagnostic);
+	}
+
+	// The path may itself contain ": " (e.g. drive letters followed by a space), so try every separator.
+	line.match_indices(": ").find_map(|(position, _)| {
+		let mut diagnostic = parse_severity_and_message(&line[position + 2..])?;
+		let (path, span) = parse_location(&line[..position])?;
+		diagnostic.path = Some(path.to_string());
+		diagnostic.span = span;
+		Some(diagnostic)
+	})
+}
+
+fn parse_location(location: &str) -> Option<(&str, Option<Span>)> {
+	if location.is_empty() {
+		return None;
+	}
+
+	let Some(inner) = location.strip_suffix(')') else {
+		return Some((location, None));
+	};
+	let open = inner.rfind('(')?;
+	let (path, numbers) = (&inner[..open], &inner[open + 1..]);
+
+	let mut numbers = numbers.split(',').map(|n| n.trim().parse::<u32>());
+	let line = numbers.next()?.ok()?;
+	let column = match numbers.next() {
+		Some(column) => Some(column.ok()?),
+		None => None,
+	};
+	if numbers.next().is_some() {
+		return None;
+	}
+
+	Some((
+		path,
+		Some(Span {
+			line,
+			column,
+			end_column: None,
+		}),
+	))
+}
+
+fn parse_severity_and_message(text: &str) -> Option<Diagnostic> {
+	const SEVERITIES: [(&str, Severity); 7] = [
+		("fatal error", Severity::Fatal),
+		("internal error", Severity::Internal),
+		("error", Severity::Error),
+		("warning", Severity::Warning),
+		("note", Severity::Note),
+		("fatal", Severity::Fatal),
+		("internal", Severity::Internal),
+	];
+
+	let (rest, severity) = SEVERITIES
+		.iter()
+		.find_map(|(name, severity)| Some((text.strip_prefix(name)?, *severity)))?;
+
+	let (code, rest) = match rest.strip_prefix(' ') {
+		Some(rest) => {
+			let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+			(Some(rest[..digits].parse().ok()?), &rest[digits..])
+		}
+		None => (None, rest),
+	};
+	let message = rest.strip_prefix(':')?.trim();
+
+	Some(Diagnostic {
+		severity,
+		code,
+		path: None,
+		span: None,
+		message: message.to_string(),
+		notes: Vec::new(),
+	})
+}
+
+/// Recognizes the `^~~~` marker Slang prints below the excerpt of the offending source line and returns the 1-based
+/// column range it covers.
+fn parse_caret_line(line: &str) -> Option<(u32, u32)> {
+	let marker = line.trim_start();
+	if !marker.starts_with('^') || !marker.trim_end().chars().all(|c| c == '^' || c == '~') {
+		return None;
+	}
+
+	let start = line.len() - marker.len();
+	let length = marker.trim_end().len();
+	Some((start as u32 + 1, (start + length) as u32 + 1))
+}
@@ -0,0 +1,325 @@
+//! Recompiling programs when the files they depend on change.
+//!
+//! ```ignore
+//! let config = SessionConfig::default()
+//!     .target(CompileTarget::Spirv, Some("spirv_1_5"))
+//!     .search_path("shaders");
+//! let mut reloader = HotReloader::new(config)?;
+//! let lighting = reloader.add_program("lighting", &["vertexMain", "fragmentMain"])?;
+//!
+//! // Once per frame:
+//! for reload in reloader.poll() {
+//!     match reload.result {
+//!         Ok(()) => rebuild_pipeline(reloader.program(reload.id)),
+//!         Err(error) => eprintln!("{error}"),
+//!     }
+//! }
+//! ```
+
+use crate::{
+	Blob, CompilerOptions, ComponentType, Diagnostic, Downcast, Error, GlobalSession, Result,
+	Session, SessionConfig, reflection,
+};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Identifies a program added to a [`HotReloader`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProgramId(usize);
+
+/// The last successful compilation of a program.
+pub struct Program {
+	linked: ComponentType,
+	entry_points: Vec<String>,
+	/// Indexed by entry point, then by target.
+	code: Vec<Vec<Blob>>,
+	diagnostics: Vec<Diagnostic>,
+}
+
+impl Program {
+	/// The linked program, e.g. for [`ComponentType::program_layout`].
+	pub fn linked(&self) -> &ComponentType {
+		&self.linked
+	}
+
+	pub fn layout(&self, target: usize) -> Result<&reflection::Shader> {
+		self.linked.layout(target as i64)
+	}
+
+	/// Names of the entry points, in the order used by [`Program::entry_point_code`].
+	pub fn entry_points(&self) -> &[String] {
+		&self.entry_points
+	}
+
+	pub fn entry_point_code(&self, entry_point: usize, target: usize) -> Option<&Blob> {
+		self.code.get(entry_point)?.get(target)
+	}
+
+	/// Warnings reported while compiling the program.
+	pub fn diagnostics(&self) -> &[Diagnostic] {
+		&self.diagnostics
+	}
+}
+
+/// The outcome of recompiling a program after one of its files changed.
+///
+/// On failure the program keeps its previous result, and the error carries the structured diagnostics.
+#[derive(Debug)]
+pub struct Reload {
+	pub id: ProgramId,
+	pub result: Result<()>,
+}
+
+/// Watched files with their modification time when they were last compiled.
+type Dependencies = Vec<(PathBuf, Option<SystemTime>)>;
+
+struct Watched {
+	module: String,
+	/// Requested entry point names. Empty means all entry points marked with `[shader(...)]`.
+	entry_points: Vec<String>,
+	dependencies: Dependencies,
+	program: Program,
+}
+
+/// Tracks the files programs depend on and recompiles programs when they change.
+///
+/// Files are polled by modification time, so this works without platform file watching APIs. Polling is cheap enough
+/// to do once per frame.
+pub struct HotReloader {
+	global_session: GlobalSession,
+	config: SessionConfig,
+	options: CompilerOptions,
+	watched: Vec<Watched>,
+}
+
+impl HotReloader {
+	/// Creates a reloader whose sessions are created from `config`. A fresh session is needed for every compilation,
+	/// since sessions cache loaded modules and never reload them from disk.
+	pub fn new(config: SessionConfig) -> Result<Self> {
+		Ok(Self {
+			global_session: GlobalSession::new().ok_or(Error::Code(crate::E_FAIL))?,
+			config,
+			options: CompilerOptions::default(),
+			watched: Vec::new(),
+		})
+	}
+
+	/// Sets the compiler options of every session, for programs added afterwards and all recompilations.
+	pub fn options(mut self, options: CompilerOptions) -> Self {
+		self.options = options;
+		self
+	}
+
+	/// Compiles `entry_points` of the module `module` and starts watching its files. All entry points marked with
+	/// `[shader(...)]` are used when `entry_points` is empty.
+	///
+	/// Fails if the initial compilation fails, since there is no previous result to fall back to.
+	pub fn add_program(&mut self, module: &str, entry_points: &[&str]) -> Result<ProgramId> {
+		let session = self
+			.config
+			.create_session(&self.global_session, &self.options)?;
+		let entry_points = entry_points
+			.iter()
+			.map(|e| e.to_string())
+			.collect::<Vec<_>>();
+		let (program, dependencies) = self.compile(&session, module, &entry_points)?;
+
+		self.watched.push(Watched {
+			module: module.to_string(),
+			entry_points,
+			dependencies,
+			program,
+		});
+		Ok(ProgramId(self.watched.len() - 1))
+	}
+
+	/// The last successful compilation of a program.
+	pub fn program(&self, id: ProgramId) -> &Program {
+		&self.watched[id.0].program
+	}
+
+	/// Recompiles every program with a changed file and reports the outcomes.
+	pub fn poll(&mut self) -> Vec<Reload> {
+		let changed = (0..self.watched.len())
+			.filter(|&i| {
+				self.watched[i]
+					.dependencies
+					.iter()
+					.any(|(path, modified)| modified_time(path) != *modified)
+			})
+			.collect::<Vec<_>>();
+		if changed.is_empty() {
+			return Vec::new();
+		}
+
+		// Programs changed by the same edit share one session, so common modules are only compiled once.
+		let session = match self
+			.config
+			.create_session(&self.global_session, &self.options)
+		{
+			Ok(session) => session,
+			Err(error) => {
+				return changed
+					.into_iter()
+					.map(|i| Reload {
+						id: ProgramId(i),
+						result: Err(error.clone()),
+					})
+					.collect();
+			}
+		};
+
+		changed
+			.into_iter()
+			.map(|i| {
+				let watched = &self.watched[i];
+				let result = self.compile(&session, &watched.module, &watched.entry_points);
+				let watched = &mut self.watched[i];
+				let result = match result {
+					Ok((program, dependencies)) => {
+						watched.program = program;
+						watched.dependencies = dependencies;
+						Ok(())
+					}
+					Err(error) => {
+						// Keep the previous result, but stop reporting the same failure until the files change again.
+						// The failed compilation may import files that are not dependencies yet, so also watch the
+						// directories they can be found in until a compilation succeeds.
+						watched.dependencies =
+							failed_dependencies(&self.config, &watched.dependencies);
+						Err(error)
+					}
+				};
+				Reload {
+					id: ProgramId(i),
+					result,
+				}
+			})
+			.collect()
+	}
+
+	/// Polls every `interval` and passes each reload to `on_reload` until it returns [`ControlFlow::Break`].
+	pub fn watch(
+		&mut self,
+		interval: Duration,
+		mut on_reload: impl FnMut(&HotReloader, Reload) -> ControlFlow<()>,
+	) {
+		loop {
+			for reload in self.poll() {
+				if on_reload(self, reload).is_break() {
+					return;
+				}
+			}
+			std::thread::sleep(interval);
+		}
+	}
+
+	fn compile(
+		&self,
+		session: &Session,
+		module_name: &str,
+		entry_point_names: &[String],
+	) -> Result<(Program, Dependencies)> {
+		let module = session.load_module(module_name)?;
+		let mut diagnostics = module.diagnostics.clone();
+
+		let dependencies = module
+			.dependency_file_paths()
+			.map(|path| {
+				let path = PathBuf::from(path);
+				let modified = modified_time(&path);
+				(path, modified)
+			})
+			.collect();
+
+		let entry_points = if entry_point_names.is_empty() {
+			module.entry_points().collect::<Vec<_>>()
+		} else {
+			entry_point_names
+				.iter()
+				.map(|name| {
+					module
+						.find_entry_point_by_name(name)
+						.ok_or_else(|| Error::missing_entry_point(module_name, name))
+				})
+				.collect::<Result<Vec<_>>>()?
+		};
+
+		let mut components = vec![module.downcast().clone()];
+		components.extend(entry_points.iter().map(|e| e.downcast().clone()));
+		let program = session.create_composite_component_type(&components)?;
+		diagnostics.extend(program.diagnostics.iter().cloned());
+		let linked = program.link()?;
+		diagnostics.extend(linked.diagnostics.iter().cloned());
+
+		let mut code = Vec::with_capacity(entry_points.len());
+		for index in 0..entry_points.len() {
+			let mut targets = Vec::with_capacity(self.config.target_count());
+			for target in 0..self.config.target_count() {
+				let blob = linked.entry_point_code(index as i64, target as i64)?;
+				diagnostics.extend(blob.diagnostics.iter().cloned());
+				targets.push(blob.into_value());
+			}
+			code.push(targets);
+		}
+
+		let program = Program {
+			linked: linked.into_value(),
+			entry_points: entry_points
+				.iter()
+				.map(|e| {
+					e.function_reflection()
+						.name()
+						.unwrap_or_default()
+						.to_string()
+				})
+				.collect(),
+			code,
+			diagnostics,
+		};
+		Ok((program, dependencies))
+	}
+}
+
+/// The dependencies of a program whose last compilation failed: its previous dependencies, plus the search paths and
+/// the directories of the dependencies along with every file in them.
+fn failed_dependencies(config: &SessionConfig, dependencies: &Dependencies) -> Dependencies {
+	let mut directories = config.search_paths().to_vec();
+	directories.extend(
+		dependencies
+			.iter()
+			.filter_map(|(path, _)| path.parent())
+			.filter(|parent| !parent.as_os_str().is_empty())
+			.map(Path::to_path_buf),
+	);
+
+	let mut paths = dependencies
+		.iter()
+		.map(|(path, _)| path.clone())
+		.collect::<Vec<_>>();
+	for directory in directories {
+		if let Ok(entries) = std::fs::read_dir(&directory) {
+			paths.extend(
+				entries
+					.filter_map(|entry| entry.ok())
+					.map(|entry| entry.path()),
+			);
+		}
+		paths.push(directory);
+	}
+	paths.sort();
+	paths.dedup();
+
+	paths
+		.into_iter()
+		.map(|path| {
+			let modified = modified_time(&path);
+			(path, modified)
+		})
+		.collect()
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
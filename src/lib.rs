@@ -1,25 +1,45 @@
 //! Rust bindings for the Slang shader language compiler
 
+#[cfg(feature = "build")]
+pub mod build;
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod diagnostics;
+#[cfg(feature = "hot_reload")]
+pub mod hot_reload;
+#[cfg(feature = "module_cache")]
+pub mod module_cache;
 pub mod reflection;
+mod session_config;
+#[cfg(feature = "variants")]
+pub mod variants;
 
 #[cfg(feature = "com_impls")]
 mod com_impls;
 #[cfg(feature = "com_impls")]
-pub use com_impls::{ComPtr, VecBlob};
+pub use com_impls::{
+	ComPtr, FileSystem, FileSystemAdapter, MemoryFileSystem, OsFileSystem, OverlayFileSystem,
+	PathType, VecBlob, normalize_path,
+};
 
 // Re-export derive macros when the derive feature is enabled
 #[cfg(feature = "derive")]
-pub use slang_derive::SlangAttribute;
+pub use slang_derive::{ShaderLayout, SlangAttribute};
 
 #[cfg(test)]
 mod tests;
 
+use std::collections::HashMap;
 use std::ffi::{CStr, CString};
 use std::marker::PhantomData;
 use std::ptr::{null, null_mut};
+use std::sync::Mutex;
 
 pub(crate) use shader_slang_sys as sys;
 
+pub use diagnostics::{Diagnostic, Severity};
+pub use session_config::SessionConfig;
+
 pub use sys::{
 	SlangBindingType as BindingType, SlangCompileTarget as CompileTarget,
 	SlangDebugInfoLevel as DebugInfoLevel, SlangDeclKind as DeclKind,
@@ -53,17 +73,56 @@ const fn uuid(data1: u32, data2: u16, data3: u16, data4: [u8; 8]) -> UUID {
 	}
 }
 
+#[derive(Clone)]
 pub enum Error {
 	Code(sys::SlangResult),
-	Blob(Blob),
+	/// The diagnostics Slang reported for a failed operation, parsed into structured form, along with the raw text
+	/// they were parsed from.
+	Diagnostics(Vec<Diagnostic>, String),
 	InvalidString(std::ffi::NulError),
 }
 
+impl Error {
+	fn from_diagnostics_blob(blob: &Blob) -> Self {
+		let raw = String::from_utf8_lossy(blob.as_slice()).into_owned();
+		Error::Diagnostics(diagnostics::parse_diagnostics(&raw), raw)
+	}
+
+	/// An error for a requested entry point that the module does not define.
+	#[cfg(any(feature = "hot_reload", feature = "variants"))]
+	pub(crate) fn missing_entry_point(module: &str, name: &str) -> Self {
+		Self::message(format!(
+			"no entry point `{name}` marked with `[shader(...)]` in module `{module}`"
+		))
+	}
+
+	/// An error detected on the Rust side, in the same form as the diagnostics Slang reports.
+	pub(crate) fn message(message: String) -> Self {
+		let diagnostic = Diagnostic {
+			severity: Severity::Error,
+			code: None,
+			path: None,
+			span: None,
+			message: message.clone(),
+			notes: Vec::new(),
+		};
+		Error::Diagnostics(vec![diagnostic], message)
+	}
+
+	/// The structured diagnostics carried by this error, if any.
+	pub fn diagnostics(&self) -> &[Diagnostic] {
+		match self {
+			Error::Diagnostics(diagnostics, _) => diagnostics,
+			_ => &[],
+		}
+	}
+}
+
 impl std::fmt::Debug for Error {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		match self {
 			Error::Code(code) => write!(f, "{}", code),
-			Error::Blob(blob) => write!(f, "{}", blob.as_str().unwrap_or_default()),
+			Error::Diagnostics(_, raw) => write!(f, "{}", raw),
 			Error::InvalidString(e) => write!(
 				f,
 				"String contains null byte at position {} - pass an input that can be used accross FFI.",
@@ -85,23 +144,136 @@ impl std::error::Error for Error {}
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The generic failure code, used when Slang reports an error without any further information.
+pub(crate) const E_FAIL: sys::SlangResult = 0x80004005_u32 as i32;
+
 pub(crate) fn succeeded(result: sys::SlangResult) -> bool {
 	result >= 0
 }
 
-fn result_from_blob(code: sys::SlangResult, blob: *mut sys::slang_IBlob) -> Result<()> {
-	if code < 0 && !blob.is_null() {
-		Err(Error::Blob(Blob(IUnknown(
-			std::ptr::NonNull::new(blob as *mut _).unwrap(),
-		))))
-	} else if code < 0 {
-		Err(Error::Code(code))
-	} else {
-		Ok(())
+const TARGET_NAMES: &[(&str, CompileTarget)] = &[
+	("spirv", CompileTarget::Spirv),
+	("spirv-asm", CompileTarget::SpirvAsm),
+	("dxil", CompileTarget::Dxil),
+	("dxbc", CompileTarget::Dxbc),
+	("hlsl", CompileTarget::Hlsl),
+	("glsl", CompileTarget::Glsl),
+	("wgsl", CompileTarget::Wgsl),
+	("metal", CompileTarget::Metal),
+	("metallib", CompileTarget::MetalLib),
+	("cuda", CompileTarget::CudaSource),
+	("ptx", CompileTarget::Ptx),
+	("cpp", CompileTarget::CppSource),
+];
+
+/// Parses a target name such as `spirv` or `dxil`, ignoring case.
+pub fn target_from_name(name: &str) -> Option<CompileTarget> {
+	TARGET_NAMES
+		.iter()
+		.find(|(n, _)| n.eq_ignore_ascii_case(name))
+		.map(|&(_, target)| target)
+}
+
+/// The names accepted by [`target_from_name`].
+pub fn target_names() -> impl Iterator<Item = &'static str> {
+	TARGET_NAMES.iter().map(|&(name, _)| name)
+}
+
+/// The conventional file extension of code compiled for `target`, or `bin` for targets without one.
+pub fn file_extension(target: CompileTarget) -> &'static str {
+	match target {
+		CompileTarget::Spirv => "spv",
+		CompileTarget::SpirvAsm => "spvasm",
+		CompileTarget::Dxil => "dxil",
+		CompileTarget::Dxbc => "dxbc",
+		CompileTarget::Hlsl => "hlsl",
+		CompileTarget::Glsl => "glsl",
+		CompileTarget::Wgsl => "wgsl",
+		CompileTarget::Metal => "metal",
+		CompileTarget::MetalLib => "metallib",
+		CompileTarget::Ptx => "ptx",
+		CompileTarget::CudaSource => "cu",
+		CompileTarget::CppSource | CompileTarget::HostCppSource => "cpp",
+		CompileTarget::CSource => "c",
+		_ => "bin",
 	}
 }
 
-#[derive(Clone, Copy)]
+/// The result of a successful operation together with the non-fatal diagnostics (usually warnings) Slang reported
+/// while performing it.
+///
+/// Dereferences to the wrapped value, so it can mostly be used in its place.
+#[derive(Clone)]
+pub struct Compiled<T> {
+	pub value: T,
+	pub diagnostics: Vec<Diagnostic>,
+}
+
+impl<T> Compiled<T> {
+	pub fn into_value(self) -> T {
+		self.value
+	}
+
+	pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+		self.diagnostics.iter().filter(|d| d.is_warning())
+	}
+
+	pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Compiled<U> {
+		Compiled {
+			value: f(self.value),
+			diagnostics: self.diagnostics,
+		}
+	}
+}
+
+impl<T> std::ops::Deref for Compiled<T> {
+	type Target = T;
+
+	fn deref(&self) -> &Self::Target {
+		&self.value
+	}
+}
+
+impl<T> std::ops::DerefMut for Compiled<T> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.value
+	}
+}
+
+/// Takes ownership of a diagnostics blob returned by Slang and turns it into an error if `code` signals failure, or
+/// into the list of reported diagnostics otherwise.
+fn result_from_blob(
+	code: sys::SlangResult,
+	blob: *mut sys::slang_IBlob,
+) -> Result<Vec<Diagnostic>> {
+	let blob = std::ptr::NonNull::new(blob as *mut _).map(|blob| Blob(IUnknown(blob)));
+
+	match blob {
+		Some(blob) if code < 0 => Err(Error::from_diagnostics_blob(&blob)),
+		None if code < 0 => Err(Error::Code(code)),
+		Some(blob) => Ok(diagnostics::parse_diagnostics(&String::from_utf8_lossy(
+			blob.as_slice(),
+		))),
+		None => Ok(Vec::new()),
+	}
+}
+
+fn module_from_raw(
+	module: *mut sys::slang_IModule,
+	diagnostics: *mut sys::slang_IBlob,
+) -> Result<Compiled<Module>> {
+	let diagnostics = result_from_blob(if module.is_null() { E_FAIL } else { 0 }, diagnostics)?;
+
+	let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
+	unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
+
+	Ok(Compiled {
+		value: module,
+		diagnostics,
+	})
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ProfileID(sys::SlangProfileID);
 
 impl ProfileID {
@@ -264,9 +436,29 @@ impl GlobalSession {
 	pub fn create_session(&self, desc: &SessionDesc) -> Option<Session> {
 		let mut session = null_mut();
 		vcall!(self, createSession(&**desc, &mut session));
-		Some(Session(IUnknown(std::ptr::NonNull::new(
-			session as *mut _,
-		)?)))
+		let session = Session(IUnknown(std::ptr::NonNull::new(session as *mut _)?));
+
+		let targets = match desc.targetCount {
+			0 => &[][..],
+			count => unsafe { std::slice::from_raw_parts(desc.targets, count as usize) },
+		};
+		let targets = targets
+			.iter()
+			.map(|target| TargetKey {
+				format: target.format,
+				profile: ProfileID(target.profile),
+			})
+			.collect();
+		let digest = self
+			.session_desc_digest(desc)
+			.map(|digest| digest.as_slice().to_vec())
+			.unwrap_or_default();
+		SESSIONS
+			.lock()
+			.unwrap()
+			.insert(session.address(), SessionInfo { targets, digest });
+
+		Some(session)
 	}
 
 	pub fn find_profile(&self, name: &str) -> Result<ProfileID> {
@@ -279,6 +471,19 @@ impl GlobalSession {
 		Ok(CapabilityID(vcall!(self, findCapability(name.as_ptr()))))
 	}
 
+	/// A digest of everything in `desc` that affects compilation, for use as part of a cache key.
+	pub fn session_desc_digest(&self, desc: &SessionDesc) -> Result<Blob> {
+		let mut digest = null_mut();
+		let status_code = vcall!(self, getSessionDescDigest(&**desc, &mut digest));
+		if status_code < 0 {
+			return Err(Error::Code(status_code));
+		}
+		Ok(Blob(IUnknown(
+			std::ptr::NonNull::new(digest as *mut _)
+				.expect("Slang returned a null pointer despite reporting success"),
+		)))
+	}
+
 	pub fn build_tag_string(&self) -> &str {
 		let tag = vcall!(self, getBuildTagString());
 		unsafe { CStr::from_ptr(tag).to_str().unwrap() }
@@ -289,6 +494,17 @@ impl GlobalSession {
 #[derive(Clone)]
 pub struct Session(IUnknown);
 
+/// What the sessions created by [`GlobalSession::create_session`] were created with, keyed by address, since Slang
+/// cannot be asked for it. An entry is replaced when a new session is created at the same address.
+static SESSIONS: std::sync::LazyLock<Mutex<HashMap<usize, SessionInfo>>> =
+	std::sync::LazyLock::new(Default::default);
+
+struct SessionInfo {
+	targets: Vec<TargetKey>,
+	/// See [`GlobalSession::session_desc_digest`].
+	digest: Vec<u8>,
+}
+
 unsafe impl Interface for Session {
 	type Vtable = sys::ISessionVtable;
 	const IID: UUID = uuid(
@@ -300,22 +516,38 @@ unsafe impl Interface for Session {
 }
 
 impl Session {
-	pub fn load_module(&self, name: &str) -> Result<Module> {
+	fn address(&self) -> usize {
+		unsafe { self.as_raw::<std::ffi::c_void>() as usize }
+	}
+
+	/// The format and profile of each target, in the order the session was created with.
+	pub fn targets(&self) -> Vec<TargetKey> {
+		SESSIONS
+			.lock()
+			.unwrap()
+			.get(&self.address())
+			.map(|info| info.targets.clone())
+			.unwrap_or_default()
+	}
+
+	/// The [`GlobalSession::session_desc_digest`] of the description the session was created with, or an empty
+	/// digest if it could not be computed.
+	pub fn desc_digest(&self) -> Vec<u8> {
+		SESSIONS
+			.lock()
+			.unwrap()
+			.get(&self.address())
+			.map(|info| info.digest.clone())
+			.unwrap_or_default()
+	}
+
+	pub fn load_module(&self, name: &str) -> Result<Compiled<Module>> {
 		let name = CString::new(name).map_err(Error::InvalidString)?;
 		let mut diagnostics = null_mut();
 
 		let module = vcall!(self, loadModule(name.as_ptr(), &mut diagnostics));
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
-		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
-		}
+		module_from_raw(module, diagnostics)
 	}
 
 	pub fn load_module_from_source_string(
@@ -323,7 +555,7 @@ impl Session {
 		module_name: &str,
 		path: &str,
 		source: &str,
-	) -> Result<Module> {
+	) -> Result<Compiled<Module>> {
 		let module_name = CString::new(module_name).map_err(Error::InvalidString)?;
 		let path = CString::new(path).map_err(Error::InvalidString)?;
 		let source = CString::new(source).map_err(Error::InvalidString)?;
@@ -339,16 +571,7 @@ impl Session {
 			)
 		);
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
-		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
-		}
+		module_from_raw(module, diagnostics)
 	}
 
 	#[cfg(feature = "com_impls")]
@@ -358,7 +581,7 @@ impl Session {
 		module_name: &str,
 		path: &str,
 		ir_blob: &impl com_impls::ImplementsISlangBlob,
-	) -> Result<Module> {
+	) -> Result<Compiled<Module>> {
 		self.load_module_from_ir_blob_impl(module_name, path, ir_blob)
 	}
 
@@ -369,7 +592,7 @@ impl Session {
 		module_name: &str,
 		path: &str,
 		ir_blob: &Blob,
-	) -> Result<Module> {
+	) -> Result<Compiled<Module>> {
 		self.load_module_from_ir_blob_impl(module_name, path, ir_blob)
 	}
 
@@ -378,7 +601,7 @@ impl Session {
 		module_name: &str,
 		path: &str,
 		ir_blob: &impl Interface,
-	) -> Result<Module> {
+	) -> Result<Compiled<Module>> {
 		let module_name = CString::new(module_name).map_err(Error::InvalidString)?;
 		let path = CString::new(path).map_err(Error::InvalidString)?;
 		let mut diagnostics = null_mut();
@@ -393,26 +616,178 @@ impl Session {
 			)
 		);
 
-		if module.is_null() {
-			let blob = Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			));
-			Err(Error::Blob(blob))
-		} else {
-			let module = Module(IUnknown(std::ptr::NonNull::new(module as *mut _).unwrap()));
-			unsafe { (module.as_unknown().vtable().ISlangUnknown_addRef)(module.as_raw()) };
-			Ok(module)
+		module_from_raw(module, diagnostics)
+	}
+
+	/// Checks whether a serialized module was compiled from the current contents of the source files it depends on,
+	/// with the options of this session.
+	#[cfg(feature = "com_impls")]
+	#[inline(always)]
+	pub fn is_binary_module_up_to_date(
+		&self,
+		module_path: &str,
+		binary_module: &impl com_impls::ImplementsISlangBlob,
+	) -> bool {
+		self.is_binary_module_up_to_date_impl(module_path, binary_module)
+	}
+
+	/// Checks whether a serialized module was compiled from the current contents of the source files it depends on,
+	/// with the options of this session.
+	#[cfg(not(feature = "com_impls"))]
+	#[inline(always)]
+	pub fn is_binary_module_up_to_date(&self, module_path: &str, binary_module: &Blob) -> bool {
+		self.is_binary_module_up_to_date_impl(module_path, binary_module)
+	}
+
+	fn is_binary_module_up_to_date_impl(
+		&self,
+		module_path: &str,
+		binary_module: &impl Interface,
+	) -> bool {
+		let Ok(module_path) = CString::new(module_path) else {
+			return false;
+		};
+		vcall!(
+			self,
+			isBinaryModuleUpToDate(module_path.as_ptr(), binary_module.as_raw())
+		)
+	}
+
+	/// Creates a component that makes `ty` usable through `interface` with dynamic dispatch. Slang picks the
+	/// conformance ID unless `id_override` is given.
+	pub fn create_type_conformance_component_type(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+		id_override: Option<i64>,
+	) -> Result<Compiled<TypeConformance>> {
+		let mut conformance = null_mut();
+		let mut diagnostics = null_mut();
+
+		let diagnostics = result_from_blob(
+			vcall!(
+				self,
+				createTypeConformanceComponentType(
+					ty as *const _ as *mut _,
+					interface as *const _ as *mut _,
+					&mut conformance,
+					id_override.unwrap_or(-1),
+					&mut diagnostics
+				)
+			),
+			diagnostics,
+		)?;
+
+		Ok(Compiled {
+			value: TypeConformance(IUnknown(
+				std::ptr::NonNull::new(conformance as *mut _).unwrap(),
+			)),
+			diagnostics,
+		})
+	}
+
+	/// The ID that identifies `ty` among the types conforming to `interface` in dynamically dispatched code.
+	pub fn type_conformance_witness_sequential_id(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+	) -> Result<u32> {
+		let mut id = 0;
+		let status_code = vcall!(
+			self,
+			getTypeConformanceWitnessSequentialID(
+				ty as *const _ as *mut _,
+				interface as *const _ as *mut _,
+				&mut id
+			)
+		);
+		if status_code < 0 {
+			return Err(Error::Code(status_code));
 		}
+		Ok(id)
+	}
+
+	pub fn type_conformance_witness_mangled_name(
+		&self,
+		ty: &reflection::Type,
+		interface: &reflection::Type,
+	) -> Result<String> {
+		let mut name = null_mut();
+		let status_code = vcall!(
+			self,
+			getTypeConformanceWitnessMangledName(
+				ty as *const _ as *mut _,
+				interface as *const _ as *mut _,
+				&mut name
+			)
+		);
+		if status_code < 0 {
+			return Err(Error::Code(status_code));
+		}
+
+		let name = Blob(IUnknown(
+			std::ptr::NonNull::new(name as *mut _)
+				.expect("Slang returned a null pointer despite reporting success"),
+		));
+		Ok(String::from_utf8_lossy(name.as_slice())
+			.trim_end_matches('\0')
+			.to_string())
+	}
+
+	/// Creates the conformances of `types` to `interface` and combines them into one component, to be linked
+	/// together with the program that dispatches over the interface.
+	///
+	/// Each type gets its index in `types` as its conformance ID, so IDs stored in host data stay valid when the
+	/// shaders change. Fails without creating anything if one of the types does not implement the interface.
+	pub fn create_conformance_table(
+		&self,
+		layout: &reflection::Shader,
+		interface: &reflection::Type,
+		types: &[&reflection::Type],
+	) -> Result<Compiled<ConformanceTable>> {
+		if let Some(ty) = types.iter().find(|ty| !layout.is_sub_type(ty, interface)) {
+			return Err(Error::message(format!(
+				"type `{}` does not conform to `{}`",
+				ty.name().unwrap_or_default(),
+				interface.name().unwrap_or_default()
+			)));
+		}
+
+		let mut components = Vec::with_capacity(types.len());
+		let mut ids = Vec::with_capacity(types.len());
+		let mut diagnostics = Vec::new();
+		for (id, ty) in types.iter().enumerate() {
+			let conformance =
+				self.create_type_conformance_component_type(ty, interface, Some(id as i64))?;
+			diagnostics.extend(conformance.diagnostics);
+			components.push(conformance.value.downcast().clone());
+
+			// Plain names are ambiguous for types in namespaces and specializations of generics.
+			let name = ty.full_name()?;
+			let name = name.as_str().map_err(|e| Error::message(e.to_string()))?;
+			ids.push((name.trim_end_matches('\0').to_string(), id as u32));
+		}
+
+		let component = self.create_composite_component_type(&components)?;
+		diagnostics.extend(component.diagnostics);
+
+		Ok(Compiled {
+			value: ConformanceTable {
+				component: component.value,
+				ids,
+			},
+			diagnostics,
+		})
 	}
 
 	pub fn create_composite_component_type(
 		&self,
 		components: &[ComponentType],
-	) -> Result<ComponentType> {
+	) -> Result<Compiled<ComponentType>> {
 		let mut composite_component_type = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
+		let diagnostics = result_from_blob(
 			vcall!(
 				self,
 				createCompositeComponentType(
@@ -425,9 +800,12 @@ impl Session {
 			diagnostics,
 		)?;
 
-		Ok(ComponentType(IUnknown(
-			std::ptr::NonNull::new(composite_component_type as *mut _).unwrap(),
-		)))
+		Ok(Compiled {
+			value: ComponentType(IUnknown(
+				std::ptr::NonNull::new(composite_component_type as *mut _).unwrap(),
+			)),
+			diagnostics,
+		})
 	}
 }
 
@@ -461,6 +839,15 @@ impl Metadata {
 	}
 }
 
+/// An argument for a specialization parameter of a [`ComponentType`], such as a generic type parameter or an
+/// interface-typed shader parameter.
+#[derive(Clone, Copy)]
+pub enum SpecializationArg<'a> {
+	Type(&'a reflection::Type),
+	/// A type written as Slang source, e.g. `PbrMaterial` or `Lambert<3>`, resolved in the scope of the program.
+	Expr(&'a str),
+}
+
 #[repr(transparent)]
 #[derive(Clone)]
 pub struct ComponentType(IUnknown);
@@ -476,52 +863,130 @@ unsafe impl Interface for ComponentType {
 }
 
 impl ComponentType {
+	pub fn session(&self) -> Session {
+		let session = vcall!(self, getSession());
+		let session = Session(IUnknown(std::ptr::NonNull::new(session as *mut _).unwrap()));
+		unsafe { (session.as_unknown().vtable().ISlangUnknown_addRef)(session.as_raw()) };
+		session
+	}
+
 	pub fn layout(&self, target: i64) -> Result<&reflection::Shader> {
 		let mut diagnostics = null_mut();
 		let ptr = vcall!(self, getLayout(target, &mut diagnostics));
 
-		if ptr.is_null() {
-			Err(Error::Blob(Blob(IUnknown(
-				std::ptr::NonNull::new(diagnostics as *mut _).unwrap(),
-			))))
-		} else {
-			Ok(unsafe { &*(ptr as *const _) })
-		}
+		result_from_blob(if ptr.is_null() { E_FAIL } else { 0 }, diagnostics)?;
+		Ok(unsafe { &*(ptr as *const _) })
+	}
+
+	/// Like [`layout`](Self::layout), but returns a handle that owns a reference to this component type and its session,
+	/// so the reflection data stays valid for as long as the handle exists.
+	pub fn program_layout(&self, target: i64) -> Result<ProgramLayout> {
+		let layout = std::ptr::NonNull::from(self.layout(target)?);
+
+		Ok(ProgramLayout {
+			layout,
+			target,
+			component_type: self.clone(),
+			_session: self.session(),
+		})
+	}
+
+	/// The number of arguments [`specialize`](Self::specialize) expects. See
+	/// [`reflection::Shader::specialization_params`] for what each argument must be.
+	pub fn specialization_param_count(&self) -> i64 {
+		vcall!(self, getSpecializationParamCount())
+	}
+
+	pub fn specialize(&self, args: &[SpecializationArg]) -> Result<Compiled<ComponentType>> {
+		let expressions = args
+			.iter()
+			.filter_map(|arg| match arg {
+				SpecializationArg::Expr(expr) => Some(CString::new(*expr)),
+				SpecializationArg::Type(_) => None,
+			})
+			.collect::<std::result::Result<Vec<_>, _>>()
+			.map_err(Error::InvalidString)?;
+		let mut expressions = expressions.iter();
+
+		let raw_args = args
+			.iter()
+			.map(|arg| match arg {
+				SpecializationArg::Type(ty) => sys::slang_SpecializationArg {
+					kind: sys::slang_SpecializationArg_Kind::Type,
+					__bindgen_anon_1: sys::slang_SpecializationArg__bindgen_ty_1 {
+						type_: *ty as *const _ as *mut _,
+					},
+				},
+				SpecializationArg::Expr(_) => sys::slang_SpecializationArg {
+					kind: sys::slang_SpecializationArg_Kind::Expr,
+					__bindgen_anon_1: sys::slang_SpecializationArg__bindgen_ty_1 {
+						expr: expressions.next().unwrap().as_ptr(),
+					},
+				},
+			})
+			.collect::<Vec<_>>();
+
+		let mut specialized = null_mut();
+		let mut diagnostics = null_mut();
+
+		let diagnostics = result_from_blob(
+			vcall!(
+				self,
+				specialize(
+					raw_args.as_ptr(),
+					raw_args.len() as _,
+					&mut specialized,
+					&mut diagnostics
+				)
+			),
+			diagnostics,
+		)?;
+
+		Ok(Compiled {
+			value: ComponentType(IUnknown(
+				std::ptr::NonNull::new(specialized as *mut _).unwrap(),
+			)),
+			diagnostics,
+		})
 	}
 
-	pub fn link(&self) -> Result<ComponentType> {
+	pub fn link(&self) -> Result<Compiled<ComponentType>> {
 		let mut linked_component_type = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
+		let diagnostics = result_from_blob(
 			vcall!(self, link(&mut linked_component_type, &mut diagnostics)),
 			diagnostics,
 		)?;
 
-		Ok(ComponentType(IUnknown(
-			std::ptr::NonNull::new(linked_component_type as *mut _).unwrap(),
-		)))
+		Ok(Compiled {
+			value: ComponentType(IUnknown(
+				std::ptr::NonNull::new(linked_component_type as *mut _).unwrap(),
+			)),
+			diagnostics,
+		})
 	}
 
-	pub fn target_code(&self, target: i64) -> Result<Blob> {
+	pub fn target_code(&self, target: i64) -> Result<Compiled<Blob>> {
 		let mut code = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
+		let diagnostics = result_from_blob(
 			vcall!(self, getTargetCode(target, &mut code, &mut diagnostics)),
 			diagnostics,
 		)?;
 
-		Ok(Blob(IUnknown(
-			std::ptr::NonNull::new(code as *mut _).unwrap(),
-		)))
+		Ok(Compiled {
+			value: Blob(IUnknown(std::ptr::NonNull::new(code as *mut _).unwrap())),
+			diagnostics,
+		})
 	}
 
-	pub fn entry_point_code(&self, index: i64, target: i64) -> Result<Blob> {
+	pub fn entry_point_code(&self, index: i64, target: i64) -> Result<Compiled<Blob>> {
 		let mut code = null_mut();
 		let mut diagnostics = null_mut();
 
-		result_from_blob(
+		let diagnostics = result_from_blob(
 			vcall!(
 				self,
 				getEntryPointCode(index, target, &mut code, &mut diagnostics)
@@ -529,9 +994,10 @@ impl ComponentType {
 			diagnostics,
 		)?;
 
-		Ok(Blob(IUnknown(
-			std::ptr::NonNull::new(code as *mut _).unwrap(),
-		)))
+		Ok(Compiled {
+			value: Blob(IUnknown(std::ptr::NonNull::new(code as *mut _).unwrap())),
+			diagnostics,
+		})
 	}
 
 	pub fn target_metadata(&self, target_index: i64) -> Result<Metadata> {
@@ -576,6 +1042,138 @@ impl ComponentType {
 			std::ptr::NonNull::new(metadata as *mut _).unwrap(),
 		)))
 	}
+
+	/// A hash of the code an entry point would compile to for a target, computed from the sources of all loaded
+	/// modules and the options of the session without generating code.
+	///
+	/// Returns `None` if Slang cannot compute a hash, e.g. for an out-of-range index.
+	pub fn entry_point_hash(&self, entry_point_index: i64, target_index: i64) -> Option<Blob> {
+		let mut hash = null_mut();
+		vcall!(
+			self,
+			getEntryPointHash(entry_point_index, target_index, &mut hash)
+		);
+		Some(Blob(IUnknown(std::ptr::NonNull::new(hash as *mut _)?)))
+	}
+
+	/// Compiles every entry point for each target of the session and returns the results keyed by format and profile.
+	///
+	/// Fails if two targets share a format and profile.
+	pub fn target_artifacts(&self) -> Result<Compiled<TargetArtifacts>> {
+		let targets = self.session().targets();
+		if targets.is_empty() {
+			return Err(Error::message(
+				"the targets of the session are unknown or empty".to_string(),
+			));
+		}
+
+		let mut artifacts = TargetArtifacts::with_capacity(targets.len());
+		let mut diagnostics = Vec::new();
+
+		for (target_index, key) in targets.into_iter().enumerate() {
+			let target_index = target_index as i64;
+			let layout = self.layout(target_index)?;
+
+			let mut entry_points = Vec::with_capacity(layout.entry_point_count() as usize);
+			for (index, entry_point) in layout.entry_points().enumerate() {
+				let index = index as i64;
+				let code = self.entry_point_code(index, target_index)?;
+				diagnostics.extend(code.diagnostics);
+
+				entry_points.push(EntryPointArtifact {
+					name: entry_point.name().unwrap_or_default().to_string(),
+					stage: entry_point.stage(),
+					code: code.value,
+					metadata: self.entry_point_metadata(index, target_index)?,
+				});
+			}
+
+			let artifact = TargetArtifact {
+				target_index,
+				entry_points,
+			};
+			if let Some(previous) = artifacts.insert(key, artifact) {
+				return Err(Error::message(format!(
+					"targets {} and {target_index} both have format {:?} and profile {}",
+					previous.target_index, key.format, key.profile.0
+				)));
+			}
+		}
+
+		Ok(Compiled {
+			value: artifacts,
+			diagnostics,
+		})
+	}
+}
+
+/// Identifies a target of a session by its format and profile.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TargetKey {
+	pub format: CompileTarget,
+	pub profile: ProfileID,
+}
+
+/// The results of [`ComponentType::target_artifacts`].
+pub type TargetArtifacts = HashMap<TargetKey, TargetArtifact>;
+
+/// The compiled entry points of a program for one target.
+#[derive(Clone)]
+pub struct TargetArtifact {
+	/// The index of the target in the session, as used by [`ComponentType::target_code`].
+	pub target_index: i64,
+	/// In the order of the entry points of the program.
+	pub entry_points: Vec<EntryPointArtifact>,
+}
+
+impl TargetArtifact {
+	pub fn entry_point(&self, name: &str) -> Option<&EntryPointArtifact> {
+		self.entry_points.iter().find(|e| e.name == name)
+	}
+}
+
+#[derive(Clone)]
+pub struct EntryPointArtifact {
+	pub name: String,
+	pub stage: Stage,
+	pub code: Blob,
+	pub metadata: Metadata,
+}
+
+/// The reflection data of a [`ComponentType`] for a single target, see [`ComponentType::program_layout`].
+///
+/// Unlike the reference returned by [`ComponentType::layout`] this handle keeps everything the reflection data depends
+/// on alive, so it can be stored, e.g. in a pipeline cache, independently of the objects it was obtained from.
+/// Dereferences to [`reflection::Shader`].
+#[derive(Clone)]
+pub struct ProgramLayout {
+	layout: std::ptr::NonNull<reflection::Shader>,
+	target: i64,
+	component_type: ComponentType,
+	_session: Session,
+}
+
+impl ProgramLayout {
+	pub fn shader(&self) -> &reflection::Shader {
+		// SAFETY: The layout is owned by the component type, which we keep alive.
+		unsafe { self.layout.as_ref() }
+	}
+
+	pub fn component_type(&self) -> &ComponentType {
+		&self.component_type
+	}
+
+	pub fn target(&self) -> i64 {
+		self.target
+	}
+}
+
+impl std::ops::Deref for ProgramLayout {
+	type Target = reflection::Shader;
+
+	fn deref(&self) -> &Self::Target {
+		self.shader()
+	}
 }
 
 #[repr(transparent)]
@@ -625,6 +1223,26 @@ unsafe impl Downcast<ComponentType> for TypeConformance {
 	}
 }
 
+/// The conformances created by [`Session::create_conformance_table`].
+#[derive(Clone)]
+pub struct ConformanceTable {
+	/// All conformances combined, to be added to the program before linking.
+	pub component: ComponentType,
+	/// The full name of each type, such as `shapes.Circle` or `Scaled<Circle>`, with its conformance ID, in the order
+	/// the types were given. The ID of a type is its index.
+	pub ids: Vec<(String, u32)>,
+}
+
+impl ConformanceTable {
+	/// The conformance ID of a type by its full name, to store in host data for dynamic dispatch.
+	pub fn id(&self, type_name: &str) -> Option<u32> {
+		self.ids
+			.iter()
+			.find(|(name, _)| name == type_name)
+			.map(|&(_, id)| id)
+	}
+}
+
 #[repr(transparent)]
 #[derive(Clone)]
 pub struct Module(IUnknown);
@@ -817,6 +1435,14 @@ impl<'a> SessionDesc<'a> {
 		self
 	}
 
+	/// Makes the session load all files, including `import`ed modules, through `file_system` instead of the real file
+	/// system. The session keeps its own reference to the file system.
+	#[cfg(feature = "com_impls")]
+	pub fn file_system(mut self, file_system: &'a ComPtr<FileSystemAdapter>) -> Self {
+		self.inner.fileSystem = file_system.as_raw() as _;
+		self
+	}
+
 	pub fn options(mut self, options: &'a CompilerOptions) -> Self {
 		self.inner.compilerOptionEntries = options.options.as_ptr() as _;
 		self.inner.compilerOptionEntryCount = options.options.len() as _;
@@ -0,0 +1,144 @@
+//! Reusing serialized modules across runs instead of parsing and checking their sources again.
+//!
+//! ```ignore
+//! let cache = ModuleCache::new("target/slang-modules")?;
+//!
+//! // Loads `materials.slang-module` if it matches the current sources, and recompiles and stores it otherwise.
+//! let materials = cache.load_module(&session, "materials")?;
+//! ```
+
+use crate::com_impls::{ComPtr, VecBlob};
+use crate::{Compiled, Module, Result, Session};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Stores the serialized IR of modules in a directory, one `.slang-module` file per module name and session
+/// description.
+///
+/// Files are keyed by [`Session::desc_digest`] as well as the module name, so sessions with different search paths,
+/// targets or options do not share files, even when the same name resolves to different sources. Slang checks each
+/// file against the current contents of the sources it was compiled from, including imported modules. Stale or
+/// unreadable files are replaced by compiling the module from source.
+///
+/// Only the modules passed to [`ModuleCache::load_module`] go through the cache. Modules they import are loaded from
+/// source, unless they were loaded through the cache into the same session first, so load shared modules through the
+/// cache before the modules that import them.
+pub struct ModuleCache {
+	dir: PathBuf,
+}
+
+impl ModuleCache {
+	/// Opens or creates a cache in `dir`.
+	pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+		let dir = dir.into();
+		fs::create_dir_all(&dir)?;
+		Ok(Self { dir })
+	}
+
+	pub fn dir(&self) -> &Path {
+		&self.dir
+	}
+
+	/// Loads a module like [`Session::load_module`], using the cached IR when it is up to date.
+	///
+	/// Failing to write the cache does not fail the compilation.
+	pub fn load_module(&self, session: &Session, name: &str) -> Result<Compiled<Module>> {
+		if let Some((path, blob)) = self.read(session, name)
+			&& session.is_binary_module_up_to_date(&path, &*blob)
+			&& let Ok(module) = session.load_module_from_ir_blob(name, &path, &*blob)
+		{
+			return Ok(module);
+		}
+
+		let module = session.load_module(name)?;
+		let _ = self.write(session, name, &module);
+		Ok(module)
+	}
+
+	/// Whether the cached IR of a module matches its current sources and the options of `session`.
+	pub fn is_up_to_date(&self, session: &Session, name: &str) -> bool {
+		self.read(session, name)
+			.is_some_and(|(path, blob)| session.is_binary_module_up_to_date(&path, &*blob))
+	}
+
+	pub fn remove(&self, session: &Session, name: &str) -> io::Result<()> {
+		remove_if_exists(&self.path(session, name, MODULE_EXTENSION))?;
+		remove_if_exists(&self.path(session, name, SOURCE_PATH_EXTENSION))
+	}
+
+	/// Removes every cached module.
+	pub fn clear(&self) -> io::Result<()> {
+		for file in fs::read_dir(&self.dir)? {
+			let path = file?.path();
+			if path
+				.extension()
+				.is_some_and(|e| e == MODULE_EXTENSION || e == SOURCE_PATH_EXTENSION)
+			{
+				remove_if_exists(&path)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Reads the source path the module was compiled from, and its IR.
+	fn read(&self, session: &Session, name: &str) -> Option<(String, ComPtr<VecBlob>)> {
+		let path = fs::read_to_string(self.path(session, name, SOURCE_PATH_EXTENSION)).ok()?;
+		let ir = fs::read(self.path(session, name, MODULE_EXTENSION)).ok()?;
+		Some((path, ComPtr::new(VecBlob::from_vec(ir))))
+	}
+
+	fn write(&self, session: &Session, name: &str, module: &Module) -> io::Result<()> {
+		// Modules created from source strings have no file to check the IR against.
+		let source_path = module.file_path();
+		if source_path.is_empty() {
+			return Ok(());
+		}
+
+		let ir = module.serialize().map_err(io::Error::other)?;
+		let path = |extension| self.path(session, name, extension);
+		self.write_file(&path(SOURCE_PATH_EXTENSION), source_path.as_bytes())?;
+		self.write_file(&path(MODULE_EXTENSION), ir.as_slice())
+	}
+
+	fn write_file(&self, path: &Path, contents: &[u8]) -> io::Result<()> {
+		let mut temporary = path.as_os_str().to_owned();
+		temporary.push(format!(".{}.tmp", std::process::id()));
+		fs::write(&temporary, contents)?;
+		fs::rename(&temporary, path).inspect_err(|_| {
+			let _ = fs::remove_file(&temporary);
+		})
+	}
+
+	fn path(&self, session: &Session, name: &str, extension: &str) -> PathBuf {
+		// A prefix of the digest is enough to tell the sessions of one application apart.
+		let digest = session
+			.desc_digest()
+			.iter()
+			.take(8)
+			.map(|b| format!("{b:02x}"))
+			.collect::<String>();
+		self.dir
+			.join(format!("{}-{digest}.{extension}", file_name(name)))
+	}
+}
+
+const MODULE_EXTENSION: &str = "slang-module";
+const SOURCE_PATH_EXTENSION: &str = "source-path";
+
+/// Turns a module name such as `materials/pbr` into a name usable as a single path component.
+fn file_name(name: &str) -> String {
+	name.chars()
+		.map(|c| match c {
+			'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' | '.' => c,
+			_ => '_',
+		})
+		.collect()
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+	match fs::remove_file(path) {
+		Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+		_ => Ok(()),
+	}
+}
@@ -0,0 +1,522 @@
+//! D3D12 root signatures.
+//!
+//! [`RootSignatureDesc`] is built from the register assignments Slang makes for HLSL and DXIL targets and can be
+//! written out as an HLSL root signature string, so it can be prepared without access to the D3D12 runtime.
+//!
+//! Descriptors are grouped into one descriptor table per register space and visibility, with samplers in tables of
+//! their own as D3D12 requires. Two user attributes change how a parameter is placed:
+//!
+//! - `[RootConstants]` on a constant buffer makes it a root constants parameter. The uniform parameters of an entry
+//!   point are always placed in root constants.
+//! - `[StaticSampler("MIN_MAG_MIP_LINEAR", "WRAP")]` on a sampler makes it a static sampler. Both arguments are
+//!   optional; they name a `D3D12_FILTER` and `D3D12_TEXTURE_ADDRESS_MODE` without their prefixes.
+//!
+//! Both attributes have to be declared in the shader, e.g. with
+//! `[__AttributeUsage(_AttributeTargets.Var)] struct RootConstantsAttribute {};`.
+
+use super::Shader;
+use crate::reflection::{TypeLayout, VariableLayout};
+use crate::{ParameterCategory, Stage, TypeKind};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShaderVisibility {
+	All,
+	Vertex,
+	Hull,
+	Domain,
+	Geometry,
+	Pixel,
+	Amplification,
+	Mesh,
+}
+
+impl ShaderVisibility {
+	/// Returns the visibility for parameters of an entry point. Stages without a visibility of their own, such as
+	/// compute and ray tracing, map to `All`.
+	pub fn from_stage(stage: Stage) -> Self {
+		match stage {
+			Stage::Vertex => Self::Vertex,
+			Stage::Hull => Self::Hull,
+			Stage::Domain => Self::Domain,
+			Stage::Geometry => Self::Geometry,
+			Stage::Fragment => Self::Pixel,
+			Stage::Amplification => Self::Amplification,
+			Stage::Mesh => Self::Mesh,
+			_ => Self::All,
+		}
+	}
+
+	fn hlsl_name(self) -> &'static str {
+		match self {
+			Self::All => "SHADER_VISIBILITY_ALL",
+			Self::Vertex => "SHADER_VISIBILITY_VERTEX",
+			Self::Hull => "SHADER_VISIBILITY_HULL",
+			Self::Domain => "SHADER_VISIBILITY_DOMAIN",
+			Self::Geometry => "SHADER_VISIBILITY_GEOMETRY",
+			Self::Pixel => "SHADER_VISIBILITY_PIXEL",
+			Self::Amplification => "SHADER_VISIBILITY_AMPLIFICATION",
+			Self::Mesh => "SHADER_VISIBILITY_MESH",
+		}
+	}
+}
+
+/// The D3D12 register classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RangeType {
+	Cbv,
+	Srv,
+	Uav,
+	Sampler,
+}
+
+impl RangeType {
+	pub fn from_category(category: ParameterCategory) -> Option<Self> {
+		Some(match category {
+			ParameterCategory::ConstantBuffer => Self::Cbv,
+			ParameterCategory::ShaderResource => Self::Srv,
+			ParameterCategory::UnorderedAccess => Self::Uav,
+			ParameterCategory::SamplerState => Self::Sampler,
+			_ => return None,
+		})
+	}
+
+	pub fn category(self) -> ParameterCategory {
+		match self {
+			Self::Cbv => ParameterCategory::ConstantBuffer,
+			Self::Srv => ParameterCategory::ShaderResource,
+			Self::Uav => ParameterCategory::UnorderedAccess,
+			Self::Sampler => ParameterCategory::SamplerState,
+		}
+	}
+
+	/// The HLSL register prefix: `b`, `t`, `u` or `s`.
+	pub fn register_prefix(self) -> char {
+		match self {
+			Self::Cbv => 'b',
+			Self::Srv => 't',
+			Self::Uav => 'u',
+			Self::Sampler => 's',
+		}
+	}
+
+	fn hlsl_name(self) -> &'static str {
+		match self {
+			Self::Cbv => "CBV",
+			Self::Srv => "SRV",
+			Self::Uav => "UAV",
+			Self::Sampler => "Sampler",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorRange {
+	pub range_type: RangeType,
+	pub base_register: u32,
+	pub space: u32,
+	/// Number of descriptors, or `None` for unbounded arrays.
+	pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorTable {
+	/// Ranges sorted by type and base register.
+	pub ranges: Vec<DescriptorRange>,
+	pub visibility: ShaderVisibility,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootConstants {
+	pub register: u32,
+	pub space: u32,
+	pub num_32bit_values: u32,
+	pub visibility: ShaderVisibility,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RootParameter {
+	DescriptorTable(DescriptorTable),
+	Constants(RootConstants),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StaticSampler {
+	pub register: u32,
+	pub space: u32,
+	/// A `D3D12_FILTER` without the `D3D12_` prefix, e.g. `FILTER_MIN_MAG_MIP_LINEAR`.
+	pub filter: String,
+	/// An address mode as written in root signatures, e.g. `TEXTURE_ADDRESS_WRAP`. Used for all three axes.
+	pub address_mode: String,
+	pub visibility: ShaderVisibility,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RootSignatureDesc {
+	/// Root constants first, then descriptor tables ordered by visibility and space.
+	pub parameters: Vec<RootParameter>,
+	pub static_samplers: Vec<StaticSampler>,
+	/// Set when the program has a vertex entry point.
+	pub allow_input_assembler_input_layout: bool,
+}
+
+impl RootSignatureDesc {
+	pub fn new(shader: &Shader) -> Self {
+		let mut builder = Builder::default();
+
+		match shader.global_params_var_layout() {
+			Some(globals) => builder.add_globals(globals),
+			None => {
+				for parameter in shader.parameters() {
+					builder.add_var(parameter, Base::default(), ShaderVisibility::All);
+				}
+			}
+		}
+
+		let mut allow_input_assembler_input_layout = false;
+		for entry_point in shader.entry_points() {
+			let visibility = ShaderVisibility::from_stage(entry_point.stage());
+			allow_input_assembler_input_layout |= entry_point.stage() == Stage::Vertex;
+
+			if let Some(params) = entry_point.var_layout() {
+				builder.add_entry_point(params, visibility);
+			}
+		}
+
+		let mut parameters = builder
+			.root_constants
+			.into_iter()
+			.map(RootParameter::Constants)
+			.collect::<Vec<_>>();
+		parameters.extend(
+			builder
+				.tables
+				.into_iter()
+				.map(|((visibility, _, _), mut ranges)| {
+					ranges.sort_by_key(|r| (r.range_type, r.base_register));
+					RootParameter::DescriptorTable(DescriptorTable { ranges, visibility })
+				}),
+		);
+
+		Self {
+			parameters,
+			static_samplers: builder.static_samplers,
+			allow_input_assembler_input_layout,
+		}
+	}
+
+	/// Writes the root signature in the HLSL root signature language, as accepted by `[RootSignature(...)]` and
+	/// `dxc -rootsig-define`. The returned string is not quoted.
+	pub fn to_hlsl_string(&self) -> String {
+		let mut items = Vec::new();
+
+		if self.allow_input_assembler_input_layout {
+			items.push("RootFlags(ALLOW_INPUT_ASSEMBLER_INPUT_LAYOUT)".to_string());
+		}
+
+		for parameter in &self.parameters {
+			match parameter {
+				RootParameter::Constants(c) => items.push(format!(
+					"RootConstants(num32BitConstants={}, b{}, space={}, visibility={})",
+					c.num_32bit_values,
+					c.register,
+					c.space,
+					c.visibility.hlsl_name()
+				)),
+				RootParameter::DescriptorTable(table) => {
+					let mut item = "DescriptorTable(".to_string();
+					for range in &table.ranges {
+						let count = match range.count {
+							Some(count) => count.to_string(),
+							None => "unbounded".to_string(),
+						};
+						write!(
+							item,
+							"{}({}{}, numDescriptors={}, space={}), ",
+							range.range_type.hlsl_name(),
+							range.range_type.register_prefix(),
+							range.base_register,
+							count,
+							range.space
+						)
+						.unwrap();
+					}
+					write!(item, "visibility={})", table.visibility.hlsl_name()).unwrap();
+					items.push(item);
+				}
+			}
+		}
+
+		for sampler in &self.static_samplers {
+			items.push(format!(
+				"StaticSampler(s{}, space={}, filter={}, addressU={mode}, addressV={mode}, addressW={mode}, visibility={})",
+				sampler.register,
+				sampler.space,
+				sampler.filter,
+				sampler.visibility.hlsl_name(),
+				mode = sampler.address_mode,
+			));
+		}
+
+		items.join(", ")
+	}
+}
+
+/// Register and space offsets accumulated while descending into parameter blocks.
+#[derive(Clone, Copy, Default)]
+struct Base {
+	space: usize,
+	registers: [usize; 4],
+}
+
+impl Base {
+	fn register(&self, range_type: RangeType) -> usize {
+		self.registers[range_type as usize]
+	}
+}
+
+const RANGE_TYPES: [RangeType; 4] = [
+	RangeType::Cbv,
+	RangeType::Srv,
+	RangeType::Uav,
+	RangeType::Sampler,
+];
+
+#[derive(Default)]
+struct Builder {
+	/// Keyed by visibility, space and whether the table holds samplers.
+	tables: BTreeMap<(ShaderVisibility, usize, bool), Vec<DescriptorRange>>,
+	root_constants: Vec<RootConstants>,
+	static_samplers: Vec<StaticSampler>,
+}
+
+impl Builder {
+	fn add_globals(&mut self, globals: &VariableLayout) {
+		let Some(type_layout) = globals.type_layout() else {
+			return;
+		};
+
+		// Loose uniform parameters are wrapped in a default constant buffer, `$Globals`, which needs a CBV.
+		if type_layout.kind() == TypeKind::ConstantBuffer
+			&& let (Some(container), Some(element)) = (
+				type_layout.container_var_layout(),
+				type_layout.element_var_layout(),
+			) {
+			let base = Base {
+				space: globals.binding_space_with_category(ParameterCategory::ConstantBuffer),
+				registers: RANGE_TYPES.map(|t| globals.offset(t.category())),
+			};
+			self.add_block_constant_buffer(container, type_layout, base, ShaderVisibility::All);
+			self.add_contents(element, base, ShaderVisibility::All);
+		} else {
+			self.add_contents(globals, Base::default(), ShaderVisibility::All);
+		}
+	}
+
+	fn add_entry_point(&mut self, params: &VariableLayout, visibility: ShaderVisibility) {
+		let Some(type_layout) = params.type_layout() else {
+			return;
+		};
+
+		// Loose uniform parameters are wrapped in a default constant buffer, which becomes root constants.
+		if type_layout.kind() == TypeKind::ConstantBuffer
+			&& let (Some(container), Some(element)) = (
+				type_layout.container_var_layout(),
+				type_layout.element_var_layout(),
+			) {
+			let base = Base {
+				space: params.binding_space_with_category(ParameterCategory::ConstantBuffer),
+				registers: RANGE_TYPES.map(|t| params.offset(t.category())),
+			};
+			self.add_root_constants(container, type_layout, base, visibility);
+			self.add_contents(element, base, visibility);
+		} else {
+			self.add_contents(params, Base::default(), visibility);
+		}
+	}
+
+	/// Adds the fields of a struct-typed variable, or the variable itself otherwise.
+	fn add_contents(&mut self, var: &VariableLayout, base: Base, visibility: ShaderVisibility) {
+		let Some(type_layout) = var.type_layout() else {
+			return;
+		};
+
+		if type_layout.kind() == TypeKind::Struct {
+			let base = offset_base(var, base);
+			for field in type_layout.fields() {
+				self.add_var(field, base, visibility);
+			}
+		} else {
+			self.add_var(var, base, visibility);
+		}
+	}
+
+	fn add_var(&mut self, var: &VariableLayout, base: Base, visibility: ShaderVisibility) {
+		let Some(type_layout) = var.type_layout() else {
+			return;
+		};
+
+		match type_layout.kind() {
+			TypeKind::ParameterBlock => {
+				let (Some(container), Some(element)) = (
+					type_layout.container_var_layout(),
+					type_layout.element_var_layout(),
+				) else {
+					return;
+				};
+				// A parameter block owns a register space, and its contents are numbered from zero within it.
+				let block = Base {
+					space: base.space + var.offset(ParameterCategory::SubElementRegisterSpace),
+					registers: [0; 4],
+				};
+				self.add_block_constant_buffer(container, type_layout, block, visibility);
+				self.add_contents(element, block, visibility);
+			}
+			TypeKind::Struct
+				if var
+					.categories()
+					.any(|c| c == ParameterCategory::SubElementRegisterSpace) =>
+			{
+				// Structs containing parameter blocks are walked field by field.
+				self.add_contents(var, base, visibility);
+			}
+			TypeKind::ConstantBuffer if has_attribute(var, "RootConstants") => {
+				let register =
+					base.register(RangeType::Cbv) + var.offset(ParameterCategory::ConstantBuffer);
+				let space =
+					base.space + var.binding_space_with_category(ParameterCategory::ConstantBuffer);
+				self.push_root_constants(register, space, type_layout, visibility);
+			}
+			TypeKind::SamplerState if has_attribute(var, "StaticSampler") => {
+				self.add_static_sampler(var, base, visibility);
+			}
+			_ => {
+				for range_type in RANGE_TYPES {
+					let category = range_type.category();
+					let count = type_layout.size(category);
+					if count == 0 {
+						continue;
+					}
+
+					let space = base.space + var.binding_space_with_category(category);
+					self.tables
+						.entry((visibility, space, range_type == RangeType::Sampler))
+						.or_default()
+						.push(DescriptorRange {
+							range_type,
+							base_register: (base.register(range_type) + var.offset(category))
+								as u32,
+							space: space as u32,
+							count: u32::try_from(count).ok().filter(|&c| c != u32::MAX),
+						});
+				}
+			}
+		}
+	}
+
+	/// Adds the implicit constant buffer holding the uniform data of a parameter block or of the global scope, if it
+	/// has any.
+	fn add_block_constant_buffer(
+		&mut self,
+		container: &VariableLayout,
+		block: &TypeLayout,
+		base: Base,
+		visibility: ShaderVisibility,
+	) {
+		let has_uniforms = block
+			.element_type_layout()
+			.is_some_and(|e| e.size(ParameterCategory::Uniform) > 0);
+		if !has_uniforms {
+			return;
+		}
+
+		self.tables
+			.entry((visibility, base.space, false))
+			.or_default()
+			.push(DescriptorRange {
+				range_type: RangeType::Cbv,
+				base_register: (base.register(RangeType::Cbv)
+					+ container.offset(ParameterCategory::ConstantBuffer)) as u32,
+				space: base.space as u32,
+				count: Some(1),
+			});
+	}
+
+	fn add_root_constants(
+		&mut self,
+		container: &VariableLayout,
+		buffer: &TypeLayout,
+		base: Base,
+		visibility: ShaderVisibility,
+	) {
+		let register =
+			base.register(RangeType::Cbv) + container.offset(ParameterCategory::ConstantBuffer);
+		self.push_root_constants(register, base.space, buffer, visibility);
+	}
+
+	fn push_root_constants(
+		&mut self,
+		register: usize,
+		space: usize,
+		buffer: &TypeLayout,
+		visibility: ShaderVisibility,
+	) {
+		let size = buffer
+			.element_type_layout()
+			.map_or(0, |e| e.size(ParameterCategory::Uniform));
+		if size == 0 {
+			return;
+		}
+
+		self.root_constants.push(RootConstants {
+			register: register as u32,
+			space: space as u32,
+			num_32bit_values: size.div_ceil(4) as u32,
+			visibility,
+		});
+	}
+
+	fn add_static_sampler(
+		&mut self,
+		var: &VariableLayout,
+		base: Base,
+		visibility: ShaderVisibility,
+	) {
+		let attribute = var.variable().and_then(|v| {
+			v.user_attributes()
+				.find(|a| a.name() == Some("StaticSampler"))
+		});
+		let argument = |index| attribute.and_then(|a| a.argument_value_string(index));
+
+		let category = ParameterCategory::SamplerState;
+		self.static_samplers.push(StaticSampler {
+			register: (base.register(RangeType::Sampler) + var.offset(category)) as u32,
+			space: (base.space + var.binding_space_with_category(category)) as u32,
+			filter: format!("FILTER_{}", argument(0).unwrap_or("MIN_MAG_MIP_LINEAR")),
+			address_mode: format!("TEXTURE_ADDRESS_{}", argument(1).unwrap_or("WRAP")),
+			visibility,
+		});
+	}
+}
+
+/// Adds the register offsets of `var` to `base`.
+fn offset_base(var: &VariableLayout, base: Base) -> Base {
+	Base {
+		space: base.space,
+		registers: RANGE_TYPES.map(|t| base.register(t) + var.offset(t.category())),
+	}
+}
+
+fn has_attribute(var: &VariableLayout, name: &str) -> bool {
+	var.variable()
+		.is_some_and(|v| v.user_attributes().any(|a| a.name() == Some(name)))
+}
@@ -0,0 +1,440 @@
+//! Graphics API binding layouts derived from reflection.
+//!
+//! [`PipelineLayoutDesc`] walks a program layout the way a Vulkan pipeline layout is built: one descriptor set layout
+//! per set index, with every `ParameterBlock` in its own set, plus the push constant ranges. The result is plain data
+//! that maps directly onto the corresponding Vulkan structures without depending on a Vulkan crate.
+
+use super::{Shader, TypeLayout, VariableLayout};
+use crate::{BindingType, ImageFormat, ParameterCategory, Stage, TypeKind};
+use std::collections::BTreeMap;
+use std::ops::{BitOr, BitOrAssign};
+
+pub mod d3d12;
+pub mod webgpu;
+
+/// A set of shader stages. The bit values match `VkShaderStageFlagBits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StageFlags(pub u32);
+
+impl StageFlags {
+	pub const NONE: Self = Self(0);
+	pub const VERTEX: Self = Self(0x1);
+	pub const HULL: Self = Self(0x2);
+	pub const DOMAIN: Self = Self(0x4);
+	pub const GEOMETRY: Self = Self(0x8);
+	pub const FRAGMENT: Self = Self(0x10);
+	pub const COMPUTE: Self = Self(0x20);
+	pub const AMPLIFICATION: Self = Self(0x40);
+	pub const MESH: Self = Self(0x80);
+	pub const RAY_GENERATION: Self = Self(0x100);
+	pub const ANY_HIT: Self = Self(0x200);
+	pub const CLOSEST_HIT: Self = Self(0x400);
+	pub const MISS: Self = Self(0x800);
+	pub const INTERSECTION: Self = Self(0x1000);
+	pub const CALLABLE: Self = Self(0x2000);
+	pub const ALL: Self = Self(0x7fff_ffff);
+
+	pub fn from_stage(stage: Stage) -> Self {
+		match stage {
+			Stage::Vertex => Self::VERTEX,
+			Stage::Hull => Self::HULL,
+			Stage::Domain => Self::DOMAIN,
+			Stage::Geometry => Self::GEOMETRY,
+			Stage::Fragment => Self::FRAGMENT,
+			Stage::Compute => Self::COMPUTE,
+			Stage::Amplification => Self::AMPLIFICATION,
+			Stage::Mesh => Self::MESH,
+			Stage::RayGeneration => Self::RAY_GENERATION,
+			Stage::AnyHit => Self::ANY_HIT,
+			Stage::ClosestHit => Self::CLOSEST_HIT,
+			Stage::Miss => Self::MISS,
+			Stage::Intersection => Self::INTERSECTION,
+			Stage::Callable => Self::CALLABLE,
+			_ => Self::NONE,
+		}
+	}
+
+	pub fn bits(self) -> u32 {
+		self.0
+	}
+
+	pub fn is_empty(self) -> bool {
+		self.0 == 0
+	}
+
+	pub fn contains(self, other: Self) -> bool {
+		self.0 & other.0 == other.0
+	}
+}
+
+impl BitOr for StageFlags {
+	type Output = Self;
+
+	fn bitor(self, rhs: Self) -> Self {
+		Self(self.0 | rhs.0)
+	}
+}
+
+impl BitOrAssign for StageFlags {
+	fn bitor_assign(&mut self, rhs: Self) {
+		self.0 |= rhs.0;
+	}
+}
+
+/// Descriptor types, named after their `VkDescriptorType` equivalents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DescriptorType {
+	Sampler,
+	CombinedImageSampler,
+	SampledImage,
+	StorageImage,
+	UniformTexelBuffer,
+	StorageTexelBuffer,
+	UniformBuffer,
+	StorageBuffer,
+	InputAttachment,
+	InlineUniformBlock,
+	AccelerationStructure,
+}
+
+impl DescriptorType {
+	/// Returns the descriptor type used for a Slang binding type, or `None` for bindings that are not descriptors
+	/// (push constants, varyings and parameter blocks, which get their own set).
+	pub fn from_binding_type(binding_type: BindingType) -> Option<Self> {
+		Some(match binding_type {
+			BindingType::Sampler => Self::Sampler,
+			BindingType::CombinedTextureSampler => Self::CombinedImageSampler,
+			BindingType::Texture => Self::SampledImage,
+			BindingType::MutableTeture => Self::StorageImage,
+			BindingType::TypedBuffer => Self::UniformTexelBuffer,
+			BindingType::MutableTypedBuffer => Self::StorageTexelBuffer,
+			BindingType::ConstantBuffer => Self::UniformBuffer,
+			BindingType::RawBuffer | BindingType::MutableRawBuffer => Self::StorageBuffer,
+			BindingType::InputRenderTarget => Self::InputAttachment,
+			BindingType::InlineUniformData => Self::InlineUniformBlock,
+			BindingType::RayTracingAccelerationStructure => Self::AccelerationStructure,
+			_ => return None,
+		})
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorBinding {
+	pub binding: u32,
+	pub descriptor_type: DescriptorType,
+	pub binding_type: BindingType,
+	/// Number of descriptors, or `None` for unbounded arrays.
+	pub count: Option<u32>,
+	pub stages: StageFlags,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorSetLayoutDesc {
+	pub set: u32,
+	/// Bindings sorted by binding index.
+	pub bindings: Vec<DescriptorBinding>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PushConstantRange {
+	pub offset: u32,
+	pub size: u32,
+	pub stages: StageFlags,
+}
+
+/// The descriptor set layouts and push constant ranges of a program.
+///
+/// Reflection does not say which stages use a global parameter, so global bindings are visible to the stages of all
+/// entry points in the program (or [`StageFlags::ALL`] if it has none). Entry point parameters are visible to their
+/// own stage only.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineLayoutDesc {
+	/// Descriptor sets sorted by set index. Set indices without bindings are omitted.
+	pub descriptor_sets: Vec<DescriptorSetLayoutDesc>,
+	pub push_constant_ranges: Vec<PushConstantRange>,
+}
+
+impl PipelineLayoutDesc {
+	pub fn new(shader: &Shader) -> Self {
+		let mut sets = BTreeMap::<u32, BTreeMap<u32, DescriptorBinding>>::new();
+		let mut push_constant_ranges = Vec::<PushConstantRange>::new();
+
+		walk(shader, &mut |item| match item {
+			Item::Descriptor(d) => {
+				let Some(descriptor_type) = DescriptorType::from_binding_type(d.binding_type)
+				else {
+					return;
+				};
+				sets.entry(d.set)
+					.or_default()
+					.entry(d.binding)
+					.and_modify(|b| b.stages |= d.stages)
+					.or_insert(DescriptorBinding {
+						binding: d.binding,
+						descriptor_type,
+						binding_type: d.binding_type,
+						count: bounded_count(d.count),
+						stages: d.stages,
+					});
+			}
+			Item::PushConstants { size, stages } => {
+				let size = size as u32;
+				match push_constant_ranges
+					.iter_mut()
+					.find(|r| r.offset == 0 && r.size == size)
+				{
+					Some(range) => range.stages |= stages,
+					None => push_constant_ranges.push(PushConstantRange {
+						offset: 0,
+						size,
+						stages,
+					}),
+				}
+			}
+		});
+
+		Self {
+			descriptor_sets: sets
+				.into_iter()
+				.map(|(set, bindings)| DescriptorSetLayoutDesc {
+					set,
+					bindings: bindings.into_values().collect(),
+				})
+				.collect(),
+			push_constant_ranges,
+		}
+	}
+
+	pub fn descriptor_set(&self, set: u32) -> Option<&DescriptorSetLayoutDesc> {
+		self.descriptor_sets.iter().find(|s| s.set == set)
+	}
+}
+
+/// Shorthand for `PipelineLayoutDesc::new(shader).descriptor_sets`.
+pub fn descriptor_set_layouts(shader: &Shader) -> Vec<DescriptorSetLayoutDesc> {
+	PipelineLayoutDesc::new(shader).descriptor_sets
+}
+
+/// Shorthand for `PipelineLayoutDesc::new(shader).push_constant_ranges`.
+pub fn push_constant_ranges(shader: &Shader) -> Vec<PushConstantRange> {
+	PipelineLayoutDesc::new(shader).push_constant_ranges
+}
+
+const SLOT: ParameterCategory = ParameterCategory::DescriptorTableSlot;
+
+/// A descriptor found while walking a program layout.
+struct Descriptor<'a> {
+	set: u32,
+	binding: u32,
+	binding_type: BindingType,
+	count: i64,
+	stages: StageFlags,
+	/// The type layout of the bound value. For constant buffers and parameter blocks this is the buffer or block,
+	/// not its element.
+	leaf: Option<&'a TypeLayout>,
+	image_format: Option<ImageFormat>,
+}
+
+enum Item<'a> {
+	Descriptor(Descriptor<'a>),
+	PushConstants { size: usize, stages: StageFlags },
+}
+
+/// Calls `visit` for every descriptor and push constant block of `shader`.
+fn walk<'a>(shader: &'a Shader, visit: &mut dyn FnMut(Item<'a>)) {
+	let all_stages = shader.entry_points().fold(StageFlags::NONE, |flags, e| {
+		flags | StageFlags::from_stage(e.stage())
+	});
+	let all_stages = if all_stages.is_empty() {
+		StageFlags::ALL
+	} else {
+		all_stages
+	};
+
+	let mut walker = Walker { visit };
+	if let Some(globals) = shader.global_params_var_layout() {
+		walker.add_scope(globals, all_stages);
+	}
+	for entry_point in shader.entry_points() {
+		if let Some(params) = entry_point.var_layout() {
+			walker.add_scope(params, StageFlags::from_stage(entry_point.stage()));
+		}
+	}
+}
+
+struct Walker<'v, 'a> {
+	visit: &'v mut dyn FnMut(Item<'a>),
+}
+
+impl<'a> Walker<'_, 'a> {
+	/// Adds the global scope or an entry point's parameters.
+	///
+	/// If the scope has loose uniform parameters its type layout wraps them in a default constant buffer, or in a
+	/// push constant block for Vulkan entry points.
+	fn add_scope(&mut self, var_layout: &'a VariableLayout, stages: StageFlags) {
+		let Some(type_layout) = var_layout.type_layout() else {
+			return;
+		};
+		let set = var_layout.binding_space_with_category(SLOT);
+		let binding = var_layout.offset(SLOT);
+
+		match type_layout.kind() {
+			TypeKind::ConstantBuffer | TypeKind::ParameterBlock => {
+				let (Some(container), Some(element)) = (
+					type_layout.container_var_layout(),
+					type_layout.element_var_layout(),
+				) else {
+					return;
+				};
+				let Some(element_layout) = element.type_layout() else {
+					return;
+				};
+
+				let uniform_size = element_layout.size(ParameterCategory::Uniform);
+				if uniform_size > 0 {
+					if container
+						.categories()
+						.any(|c| c == ParameterCategory::PushConstantBuffer)
+					{
+						self.add_push_constants(uniform_size, stages);
+					} else {
+						self.add_constant_buffer(
+							set + container.binding_space_with_category(SLOT),
+							binding + container.offset(SLOT),
+							type_layout,
+							stages,
+						);
+					}
+				}
+
+				self.add_contents(
+					element_layout,
+					set + element.binding_space_with_category(SLOT),
+					binding + element.offset(SLOT),
+					stages,
+				);
+			}
+			_ => self.add_contents(type_layout, set, binding, stages),
+		}
+	}
+
+	/// Adds the descriptors of `type_layout` and recurses into its parameter blocks and push constant blocks.
+	fn add_contents(
+		&mut self,
+		type_layout: &'a TypeLayout,
+		set: usize,
+		binding: usize,
+		stages: StageFlags,
+	) {
+		for range in 0..type_layout.binding_range_count() {
+			// These are sub-objects, handled below.
+			if matches!(
+				type_layout.binding_range_type(range),
+				BindingType::ParameterBlock | BindingType::PushConstant
+			) {
+				continue;
+			}
+
+			let set_index = type_layout.binding_range_descriptor_set_index(range);
+			let space = set + type_layout.descriptor_set_space_offset(set_index) as usize;
+			let first = type_layout.binding_range_first_descriptor_range_index(range);
+			let count = type_layout.binding_range_descriptor_range_count(range);
+
+			for descriptor_range in first..first + count {
+				if type_layout.descriptor_set_descriptor_range_category(set_index, descriptor_range)
+					!= SLOT
+				{
+					continue;
+				}
+
+				let index_offset = type_layout
+					.descriptor_set_descriptor_range_index_offset(set_index, descriptor_range);
+				(self.visit)(Item::Descriptor(Descriptor {
+					set: space as u32,
+					binding: (binding + index_offset as usize) as u32,
+					binding_type: type_layout
+						.descriptor_set_descriptor_range_type(set_index, descriptor_range),
+					count: type_layout.descriptor_set_descriptor_range_descriptor_count(
+						set_index,
+						descriptor_range,
+					),
+					stages,
+					leaf: type_layout.binding_range_leaf_type_layout(range),
+					image_format: Some(type_layout.binding_range_image_format(range)),
+				}));
+			}
+		}
+
+		for sub_object in 0..type_layout.sub_object_range_count() {
+			let range = type_layout.sub_object_range_binding_range_index(sub_object);
+			let Some(leaf) = type_layout.binding_range_leaf_type_layout(range) else {
+				continue;
+			};
+
+			match type_layout.binding_range_type(range) {
+				BindingType::ParameterBlock => {
+					let block_set =
+						set + type_layout.sub_object_range_space_offset(sub_object) as usize;
+					self.add_parameter_block(leaf, block_set, stages);
+				}
+				BindingType::PushConstant => {
+					if let Some(element) = leaf.element_type_layout() {
+						self.add_push_constants(element.size(ParameterCategory::Uniform), stages);
+					}
+				}
+				_ => {}
+			}
+		}
+	}
+
+	/// Adds a parameter block, which owns descriptor set `set`. Its uniform data goes in an implicit constant buffer.
+	fn add_parameter_block(&mut self, block: &'a TypeLayout, set: usize, stages: StageFlags) {
+		let Some(element) = block.element_var_layout() else {
+			return;
+		};
+		let Some(element_layout) = element.type_layout() else {
+			return;
+		};
+
+		if element_layout.size(ParameterCategory::Uniform) > 0 {
+			let binding = block.container_var_layout().map_or(0, |c| c.offset(SLOT));
+			self.add_constant_buffer(set, binding, block, stages);
+		}
+		self.add_contents(element_layout, set, element.offset(SLOT), stages);
+	}
+
+	fn add_constant_buffer(
+		&mut self,
+		set: usize,
+		binding: usize,
+		buffer: &'a TypeLayout,
+		stages: StageFlags,
+	) {
+		(self.visit)(Item::Descriptor(Descriptor {
+			set: set as u32,
+			binding: binding as u32,
+			binding_type: BindingType::ConstantBuffer,
+			count: 1,
+			stages,
+			leaf: Some(buffer),
+			image_format: None,
+		}));
+	}
+
+	fn add_push_constants(&mut self, size: usize, stages: StageFlags) {
+		if size > 0 {
+			(self.visit)(Item::PushConstants { size, stages });
+		}
+	}
+}
+
+/// Converts a descriptor count to `None` for unbounded arrays.
+fn bounded_count(count: i64) -> Option<u32> {
+	u32::try_from(count).ok().filter(|&c| c != u32::MAX)
+}
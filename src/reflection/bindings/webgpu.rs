@@ -0,0 +1,407 @@
+//! WebGPU bind group layouts.
+//!
+//! The types mirror `GPUBindGroupLayoutEntry` and its member dictionaries, using the variant names of the `wgpu`
+//! crate so that converting them is a plain `match`. WGSL has no push constants, so only descriptors are included.
+
+use super::{Item, Shader, StageFlags, bounded_count, walk};
+use crate::reflection::TypeLayout;
+use crate::reflection::snapshot::{
+	RESOURCE_BASE_SHAPE_MASK, RESOURCE_SHAPE_ARRAY_FLAG, RESOURCE_SHAPE_MULTISAMPLE_FLAG,
+	RESOURCE_SHAPE_SHADOW_FLAG,
+};
+use crate::{BindingType, ImageFormat, ParameterCategory, ResourceAccess, ScalarType, TypeKind};
+use std::collections::BTreeMap;
+use std::num::NonZeroU64;
+
+/// WebGPU shader stage visibility. The bit values match `GPUShaderStage`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShaderStages(pub u32);
+
+impl ShaderStages {
+	pub const NONE: Self = Self(0);
+	pub const VERTEX: Self = Self(0x1);
+	pub const FRAGMENT: Self = Self(0x2);
+	pub const COMPUTE: Self = Self(0x4);
+
+	/// Keeps the stages that exist in WebGPU.
+	pub fn from_stage_flags(flags: StageFlags) -> Self {
+		let mut stages = Self::NONE;
+		if flags.contains(StageFlags::VERTEX) {
+			stages.0 |= Self::VERTEX.0;
+		}
+		if flags.contains(StageFlags::FRAGMENT) {
+			stages.0 |= Self::FRAGMENT.0;
+		}
+		if flags.contains(StageFlags::COMPUTE) {
+			stages.0 |= Self::COMPUTE.0;
+		}
+		stages
+	}
+
+	pub fn bits(self) -> u32 {
+		self.0
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BufferBindingType {
+	Uniform,
+	Storage { read_only: bool },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SamplerBindingType {
+	Filtering,
+	NonFiltering,
+	Comparison,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureSampleType {
+	Float { filterable: bool },
+	Depth,
+	Sint,
+	Uint,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureViewDimension {
+	D1,
+	D2,
+	D2Array,
+	Cube,
+	CubeArray,
+	D3,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StorageTextureAccess {
+	WriteOnly,
+	ReadOnly,
+	ReadWrite,
+}
+
+/// The texture formats a Slang `[format(...)]` attribute can name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TextureFormat {
+	R8Unorm,
+	R8Snorm,
+	R8Uint,
+	R8Sint,
+	R16Unorm,
+	R16Snorm,
+	R16Uint,
+	R16Sint,
+	R16Float,
+	Rg8Unorm,
+	Rg8Snorm,
+	Rg8Uint,
+	Rg8Sint,
+	R32Uint,
+	R32Sint,
+	R32Float,
+	Rg16Unorm,
+	Rg16Snorm,
+	Rg16Uint,
+	Rg16Sint,
+	Rg16Float,
+	Rgba8Unorm,
+	Rgba8Snorm,
+	Rgba8Uint,
+	Rgba8Sint,
+	Bgra8Unorm,
+	Rgb10a2Uint,
+	Rgb10a2Unorm,
+	Rg11b10Ufloat,
+	R64Uint,
+	Rg32Uint,
+	Rg32Sint,
+	Rg32Float,
+	Rgba16Unorm,
+	Rgba16Snorm,
+	Rgba16Uint,
+	Rgba16Sint,
+	Rgba16Float,
+	Rgba32Uint,
+	Rgba32Sint,
+	Rgba32Float,
+}
+
+impl TextureFormat {
+	/// Maps a Slang image format, returning `None` for `unknown` and formats WebGPU lacks.
+	pub fn from_image_format(format: ImageFormat) -> Option<Self> {
+		use TextureFormat::*;
+
+		// Indexed by the numeric value of `SlangImageFormat`.
+		const FORMATS: [Option<TextureFormat>; 43] = [
+			None,
+			Some(Rgba32Float),
+			Some(Rgba16Float),
+			Some(Rg32Float),
+			Some(Rg16Float),
+			Some(Rg11b10Ufloat),
+			Some(R32Float),
+			Some(R16Float),
+			Some(Rgba16Unorm),
+			Some(Rgb10a2Unorm),
+			Some(Rgba8Unorm),
+			Some(Rg16Unorm),
+			Some(Rg8Unorm),
+			Some(R16Unorm),
+			Some(R8Unorm),
+			Some(Rgba16Snorm),
+			Some(Rgba8Snorm),
+			Some(Rg16Snorm),
+			Some(Rg8Snorm),
+			Some(R16Snorm),
+			Some(R8Snorm),
+			Some(Rgba32Sint),
+			Some(Rgba16Sint),
+			Some(Rgba8Sint),
+			Some(Rg32Sint),
+			Some(Rg16Sint),
+			Some(Rg8Sint),
+			Some(R32Sint),
+			Some(R16Sint),
+			Some(R8Sint),
+			Some(Rgba32Uint),
+			Some(Rgba16Uint),
+			Some(Rgb10a2Uint),
+			Some(Rgba8Uint),
+			Some(Rg32Uint),
+			Some(Rg16Uint),
+			Some(Rg8Uint),
+			Some(R32Uint),
+			Some(R16Uint),
+			Some(R8Uint),
+			Some(R64Uint),
+			// r64i
+			None,
+			Some(Bgra8Unorm),
+		];
+
+		FORMATS.get(format as usize).copied().flatten()
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BindingKind {
+	Buffer {
+		ty: BufferBindingType,
+		has_dynamic_offset: bool,
+		min_binding_size: Option<NonZeroU64>,
+	},
+	Sampler(SamplerBindingType),
+	Texture {
+		sample_type: TextureSampleType,
+		view_dimension: TextureViewDimension,
+		multisampled: bool,
+	},
+	StorageTexture {
+		access: StorageTextureAccess,
+		/// `None` if the shader declares no format and none can be inferred from the texel type.
+		format: Option<TextureFormat>,
+		view_dimension: TextureViewDimension,
+	},
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindGroupLayoutEntry {
+	pub binding: u32,
+	pub visibility: ShaderStages,
+	pub ty: BindingKind,
+	/// Array size for binding arrays, `None` for a single binding. Unbounded arrays report `u32::MAX`, since WebGPU
+	/// needs a concrete size for them.
+	pub count: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindGroupLayoutDesc {
+	pub group: u32,
+	/// Entries sorted by binding index.
+	pub entries: Vec<BindGroupLayoutEntry>,
+}
+
+/// Returns the bind group layouts of a program compiled for WGSL, sorted by group index.
+///
+/// Reflection cannot tell whether a float texture will be sampled with filtering, so textures are reported as
+/// filterable and samplers as filtering, except for shadow textures and comparison samplers. Bindings WebGPU has no
+/// equivalent for, such as texel buffers, are skipped.
+pub fn bind_group_layouts(shader: &Shader) -> Vec<BindGroupLayoutDesc> {
+	let mut groups = BTreeMap::<u32, BTreeMap<u32, BindGroupLayoutEntry>>::new();
+
+	walk(shader, &mut |item| {
+		let Item::Descriptor(d) = item else {
+			return;
+		};
+		let Some(ty) = binding_kind(d.binding_type, d.leaf, d.image_format) else {
+			return;
+		};
+
+		let visibility = ShaderStages::from_stage_flags(d.stages);
+		groups
+			.entry(d.set)
+			.or_default()
+			.entry(d.binding)
+			.and_modify(|e| e.visibility.0 |= visibility.0)
+			.or_insert(BindGroupLayoutEntry {
+				binding: d.binding,
+				visibility,
+				ty,
+				count: bounded_count(d.count).map_or(Some(u32::MAX), |c| (c != 1).then_some(c)),
+			});
+	});
+
+	groups
+		.into_iter()
+		.map(|(group, entries)| BindGroupLayoutDesc {
+			group,
+			entries: entries.into_values().collect(),
+		})
+		.collect()
+}
+
+// Base values of `SlangResourceShape`.
+const TEXTURE_1D: u32 = 1;
+const TEXTURE_2D: u32 = 2;
+const TEXTURE_3D: u32 = 3;
+const TEXTURE_CUBE: u32 = 4;
+
+fn binding_kind(
+	binding_type: BindingType,
+	leaf: Option<&TypeLayout>,
+	image_format: Option<ImageFormat>,
+) -> Option<BindingKind> {
+	let buffer = |ty| {
+		Some(BindingKind::Buffer {
+			ty,
+			has_dynamic_offset: false,
+			min_binding_size: leaf.and_then(min_binding_size),
+		})
+	};
+
+	match binding_type {
+		BindingType::ConstantBuffer => buffer(BufferBindingType::Uniform),
+		BindingType::RawBuffer => buffer(BufferBindingType::Storage { read_only: true }),
+		BindingType::MutableRawBuffer => buffer(BufferBindingType::Storage { read_only: false }),
+		BindingType::Sampler => {
+			let comparison = leaf
+				.and_then(|l| l.ty())
+				.and_then(|t| t.name())
+				.is_some_and(|name| name.contains("Comparison"));
+			Some(BindingKind::Sampler(if comparison {
+				SamplerBindingType::Comparison
+			} else {
+				SamplerBindingType::Filtering
+			}))
+		}
+		BindingType::Texture => {
+			let shape = resource_shape(leaf?);
+			Some(BindingKind::Texture {
+				sample_type: if shape & RESOURCE_SHAPE_SHADOW_FLAG != 0 {
+					TextureSampleType::Depth
+				} else {
+					sample_type(leaf?)
+				},
+				view_dimension: view_dimension(shape)?,
+				multisampled: shape & RESOURCE_SHAPE_MULTISAMPLE_FLAG != 0,
+			})
+		}
+		BindingType::MutableTeture => {
+			let leaf = leaf?;
+			let access = match leaf.ty().map(|t| t.resource_access()) {
+				Some(ResourceAccess::Read) => StorageTextureAccess::ReadOnly,
+				Some(ResourceAccess::Write) => StorageTextureAccess::WriteOnly,
+				_ => StorageTextureAccess::ReadWrite,
+			};
+			Some(BindingKind::StorageTexture {
+				access,
+				format: image_format
+					.and_then(TextureFormat::from_image_format)
+					.or_else(|| inferred_format(leaf)),
+				view_dimension: view_dimension(resource_shape(leaf))?,
+			})
+		}
+		_ => None,
+	}
+}
+
+/// Returns the uniform size of a constant buffer's element, or the element stride of a structured buffer.
+fn min_binding_size(leaf: &TypeLayout) -> Option<NonZeroU64> {
+	let size = match leaf.kind() {
+		TypeKind::ConstantBuffer | TypeKind::ParameterBlock => {
+			leaf.element_type_layout()?.size(ParameterCategory::Uniform)
+		}
+		_ => leaf
+			.element_type_layout()?
+			.stride(ParameterCategory::Uniform),
+	};
+	NonZeroU64::new(size as u64)
+}
+
+fn resource_shape(leaf: &TypeLayout) -> u32 {
+	leaf.ty().map_or(0, |t| t.resource_shape() as u32)
+}
+
+fn view_dimension(shape: u32) -> Option<TextureViewDimension> {
+	let array = shape & RESOURCE_SHAPE_ARRAY_FLAG != 0;
+	Some(match (shape & RESOURCE_BASE_SHAPE_MASK, array) {
+		(TEXTURE_1D, _) => TextureViewDimension::D1,
+		(TEXTURE_2D, false) => TextureViewDimension::D2,
+		(TEXTURE_2D, true) => TextureViewDimension::D2Array,
+		(TEXTURE_3D, _) => TextureViewDimension::D3,
+		(TEXTURE_CUBE, false) => TextureViewDimension::Cube,
+		(TEXTURE_CUBE, true) => TextureViewDimension::CubeArray,
+		_ => return None,
+	})
+}
+
+/// Returns the scalar type and component count of a texture's texel type.
+fn texel_type(leaf: &TypeLayout) -> Option<(ScalarType, usize)> {
+	let result = leaf.ty()?.resource_result_type()?;
+	match result.kind() {
+		TypeKind::Vector => Some((result.element_type()?.scalar_type(), result.element_count())),
+		TypeKind::Scalar => Some((result.scalar_type(), 1)),
+		_ => None,
+	}
+}
+
+fn sample_type(leaf: &TypeLayout) -> TextureSampleType {
+	match texel_type(leaf).map(|(scalar, _)| scalar) {
+		Some(ScalarType::Int8 | ScalarType::Int16 | ScalarType::Int32 | ScalarType::Int64) => {
+			TextureSampleType::Sint
+		}
+		Some(ScalarType::Uint8 | ScalarType::Uint16 | ScalarType::Uint32 | ScalarType::Uint64) => {
+			TextureSampleType::Uint
+		}
+		_ => TextureSampleType::Float { filterable: true },
+	}
+}
+
+/// Picks the 32-bit format matching a storage texture's texel type when no `[format(...)]` is given.
+fn inferred_format(leaf: &TypeLayout) -> Option<TextureFormat> {
+	use TextureFormat::*;
+
+	Some(match texel_type(leaf)? {
+		(ScalarType::Float32, 1) => R32Float,
+		(ScalarType::Float32, 2) => Rg32Float,
+		(ScalarType::Float32, 4) => Rgba32Float,
+		(ScalarType::Int32, 1) => R32Sint,
+		(ScalarType::Int32, 2) => Rg32Sint,
+		(ScalarType::Int32, 4) => Rgba32Sint,
+		(ScalarType::Uint32, 1) => R32Uint,
+		(ScalarType::Uint32, 2) => Rg32Uint,
+		(ScalarType::Uint32, 4) => Rgba32Uint,
+		_ => return None,
+	})
+}
@@ -0,0 +1,536 @@
+//! Rust source generation for host-side mirrors of shader data.
+//!
+//! [`StructGenerator`] walks the [`ParameterCategory::Uniform`] layout of a constant buffer, push constant block or
+//! structured buffer element and writes `#[repr(C)]` structs that match it byte for byte. Gaps in the layout become
+//! explicit `_padN` fields, and every struct is followed by const assertions on its size and field offsets, so a layout
+//! change in the shader fails the build instead of corrupting data at runtime. It only needs reflection data, which
+//! makes it suitable for use from a `build.rs` script.
+
+use super::{ReflectionError, TypeLayout};
+use crate::{MatrixLayoutMode, ParameterCategory, ScalarType, TypeKind};
+use std::fmt::Write;
+
+const UNIFORM: ParameterCategory = ParameterCategory::Uniform;
+
+/// Generates Rust source for the structs matching one uniform layout.
+///
+/// Equivalent to `StructGenerator::new().add(name, type_layout)?.generate()`.
+pub fn generate_rust_struct(
+	name: &str,
+	type_layout: &TypeLayout,
+) -> Result<String, ReflectionError> {
+	let mut generator = StructGenerator::new();
+	generator.add(name, type_layout)?;
+	Ok(generator.generate())
+}
+
+/// Collects `#[repr(C)]` struct definitions for one or more uniform layouts.
+///
+/// Struct types nested in the layouts are emitted once under their Slang name. If the same Slang struct is laid out
+/// differently in two places (for example in a constant buffer and in a structured buffer) the second copy gets a
+/// numeric suffix.
+#[derive(Debug, Clone)]
+pub struct StructGenerator {
+	derives: Vec<String>,
+	structs: Vec<(String, String)>,
+}
+
+impl Default for StructGenerator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+struct Field {
+	name: String,
+	ty: String,
+	offset: usize,
+	size: usize,
+	doc: Option<String>,
+	/// The Slang name, if it differs from `name`.
+	shader_name: Option<String>,
+}
+
+impl StructGenerator {
+	/// Creates a generator whose structs derive `Clone` and `Copy`.
+	pub fn new() -> Self {
+		Self {
+			derives: vec!["Clone".to_string(), "Copy".to_string()],
+			structs: Vec::new(),
+		}
+	}
+
+	/// Adds a derive to every generated struct, e.g. `"bytemuck::Pod"`.
+	///
+	/// All padding is explicit, so the generated structs satisfy the requirements of `bytemuck::Pod`. Deriving
+	/// `shader_slang::ShaderLayout` lets tests verify them with [`check_layout`](super::check_layout).
+	pub fn derive(mut self, path: &str) -> Self {
+		self.derives.push(path.to_string());
+		self
+	}
+
+	/// Generates a struct called `name` for `type_layout` and any structs it depends on.
+	///
+	/// `type_layout` may be a struct, or a constant buffer, parameter block, push constant block or structured buffer
+	/// wrapping one, in which case the element layout is used. Fails if a struct with a different layout was already
+	/// generated under `name`.
+	pub fn add(
+		&mut self,
+		name: &str,
+		type_layout: &TypeLayout,
+	) -> Result<&mut Self, ReflectionError> {
+		let layout = data_layout(type_layout)?;
+		if layout.kind() != TypeKind::Struct {
+			return Err(ReflectionError::TypeMismatch {
+				expected: "struct".to_string(),
+				actual: format!("{:?}", layout.kind()),
+			});
+		}
+
+		let name = identifier(name);
+		let generated = self.emit_struct(&name, layout)?;
+		if generated != name {
+			return Err(ReflectionError::TypeMismatch {
+				expected: format!("`{name}` to be unused or have the same layout"),
+				actual: format!("a different struct named `{name}`"),
+			});
+		}
+		Ok(self)
+	}
+
+	/// Returns the source of all structs added so far.
+	pub fn generate(&self) -> String {
+		let mut out = String::new();
+		for (i, (_, source)) in self.structs.iter().enumerate() {
+			if i > 0 {
+				out.push('\n');
+			}
+			out.push_str(source);
+		}
+		out
+	}
+
+	fn emit_struct(&mut self, name: &str, layout: &TypeLayout) -> Result<String, ReflectionError> {
+		let mut fields = Vec::new();
+		for field in layout.fields() {
+			let field_layout = field.type_layout().ok_or(ReflectionError::UnexpectedNull)?;
+			// Resources and other opaque members take no space in the uniform data.
+			if field_layout.size(UNIFORM) == 0 {
+				continue;
+			}
+
+			let field_name = field.name().unwrap_or("field");
+			let context = format!("{name}{}", pascal_case(field_name));
+			let (ty, size, doc) = self.rust_type(&context, field_layout)?;
+			let rust_name = field_identifier(field_name);
+			let shader_name =
+				(rust_name.trim_start_matches("r#") != field_name).then(|| field_name.to_string());
+			fields.push(Field {
+				name: rust_name,
+				ty,
+				offset: field.offset(UNIFORM),
+				size,
+				doc,
+				shader_name,
+			});
+		}
+		fields.sort_by_key(|f| f.offset);
+
+		self.emit_fields(name, fields, layout.size(UNIFORM))
+	}
+
+	/// Returns the Rust type, its size in bytes and an optional doc line for a uniform value.
+	fn rust_type(
+		&mut self,
+		context: &str,
+		layout: &TypeLayout,
+	) -> Result<(String, usize, Option<String>), ReflectionError> {
+		let size = layout.size(UNIFORM);
+		match layout.kind() {
+			TypeKind::Scalar => {
+				let (ty, scalar_size) = scalar(layout)?;
+				check_size(context, size, scalar_size)?;
+				Ok((ty.to_string(), size, None))
+			}
+			TypeKind::Vector => {
+				let (ty, scalar_size) = scalar(layout)?;
+				let count = layout.element_count().unwrap_or(0);
+				check_size(context, size, count * scalar_size)?;
+				Ok((format!("[{ty}; {count}]"), size, None))
+			}
+			TypeKind::Matrix => matrix(layout),
+			TypeKind::Struct => {
+				let name = layout
+					.name()
+					.map(identifier)
+					.unwrap_or_else(|| context.to_string());
+				let name = self.emit_struct(&name, layout)?;
+				Ok((name, size, None))
+			}
+			TypeKind::Array => self.array(context, layout),
+			kind => Err(ReflectionError::TypeMismatch {
+				expected: "scalar, vector, matrix, array or struct".to_string(),
+				actual: format!("{kind:?} for `{context}`"),
+			}),
+		}
+	}
+
+	fn array(
+		&mut self,
+		context: &str,
+		layout: &TypeLayout,
+	) -> Result<(String, usize, Option<String>), ReflectionError> {
+		let count = layout.element_count().unwrap_or(0);
+		if count == 0 || count == usize::MAX {
+			return Err(ReflectionError::TypeMismatch {
+				expected: "sized array".to_string(),
+				actual: format!("unbounded array for `{context}`"),
+			});
+		}
+
+		let element_layout = layout
+			.element_type_layout()
+			.ok_or(ReflectionError::UnexpectedNull)?;
+		let (element, element_size, doc) =
+			self.rust_type(&format!("{context}Element"), element_layout)?;
+		let stride = layout.element_stride(UNIFORM);
+		let size = layout.size(UNIFORM);
+
+		if stride == element_size {
+			check_size(context, size, count * stride)?;
+			return Ok((format!("[{element}; {count}]"), size, doc));
+		}
+
+		// Elements are padded up to the array stride. Wrap each one with its padding; if the last element is not
+		// padded (as in D3D constant buffers) the array becomes a struct with the last element stored separately.
+		let padded = format!("{context}Padded");
+		self.emit_fields(
+			&padded,
+			vec![Field {
+				name: "value".to_string(),
+				ty: element.clone(),
+				offset: 0,
+				size: element_size,
+				doc,
+				shader_name: None,
+			}],
+			stride,
+		)?;
+
+		if size == count * stride {
+			return Ok((format!("[{padded}; {count}]"), size, None));
+		}
+
+		check_size(context, size, (count - 1) * stride + element_size)?;
+		let name = format!("{context}Array");
+		self.emit_fields(
+			&name,
+			vec![
+				Field {
+					name: "elements".to_string(),
+					ty: format!("[{padded}; {}]", count - 1),
+					offset: 0,
+					size: (count - 1) * stride,
+					doc: Some(format!("Elements `0..{}`.", count - 1)),
+					shader_name: None,
+				},
+				Field {
+					name: "last".to_string(),
+					ty: element,
+					offset: (count - 1) * stride,
+					size: element_size,
+					doc: Some(format!(
+						"Element `{}`, which has no trailing padding.",
+						count - 1
+					)),
+					shader_name: None,
+				},
+			],
+			size,
+		)?;
+		Ok((name, size, None))
+	}
+
+	/// Registers a struct with `fields`, reusing an identical struct or picking a free name if `name` is taken.
+	fn emit_fields(
+		&mut self,
+		name: &str,
+		fields: Vec<Field>,
+		size: usize,
+	) -> Result<String, ReflectionError> {
+		let mut candidate = name.to_string();
+		let mut suffix = 1;
+		loop {
+			let source = self.struct_source(&candidate, &fields, size)?;
+			match self.structs.iter().find(|(n, _)| *n == candidate) {
+				None => {
+					self.structs.push((candidate.clone(), source));
+					return Ok(candidate);
+				}
+				Some((_, existing)) if *existing == source => return Ok(candidate),
+				Some(_) => {}
+			}
+
+			candidate = format!("{name}{suffix}");
+			suffix += 1;
+		}
+	}
+
+	fn struct_source(
+		&self,
+		name: &str,
+		fields: &[Field],
+		size: usize,
+	) -> Result<String, ReflectionError> {
+		// `#[slang(name)]` is only accepted on structs deriving `ShaderLayout`.
+		let tag_renamed = self
+			.derives
+			.iter()
+			.any(|d| d.rsplit("::").next() == Some("ShaderLayout"));
+
+		let mut body = String::new();
+		let mut asserts = String::new();
+		let mut cursor = 0;
+		let mut pad = 0;
+
+		let mut pad_to = |body: &mut String, cursor: &mut usize, offset: usize| {
+			if offset > *cursor {
+				// Skip numbers already used by shader fields called `_padN`.
+				while fields.iter().any(|f| f.name == format!("_pad{pad}")) {
+					pad += 1;
+				}
+				writeln!(body, "\tpub _pad{pad}: [u8; {}],", offset - *cursor).unwrap();
+				pad += 1;
+				*cursor = offset;
+			}
+		};
+
+		for field in fields {
+			if field.offset < cursor {
+				return Err(ReflectionError::TypeMismatch {
+					expected: format!("`{name}::{}` at offset {cursor} or later", field.name),
+					actual: format!("offset {}", field.offset),
+				});
+			}
+			pad_to(&mut body, &mut cursor, field.offset);
+
+			if let Some(doc) = &field.doc {
+				writeln!(body, "\t/// {doc}").unwrap();
+			}
+			if tag_renamed && let Some(shader_name) = &field.shader_name {
+				writeln!(body, "\t#[slang(name = \"{shader_name}\")]").unwrap();
+			}
+			writeln!(body, "\tpub {}: {},", field.name, field.ty).unwrap();
+			writeln!(
+				asserts,
+				"const _: () = assert!(::core::mem::offset_of!({name}, {}) == {});",
+				field.name, field.offset
+			)
+			.unwrap();
+			cursor = field.offset + field.size;
+		}
+
+		if size < cursor {
+			return Err(ReflectionError::TypeMismatch {
+				expected: format!("`{name}` to be at least {cursor} bytes"),
+				actual: format!("{size} bytes"),
+			});
+		}
+		pad_to(&mut body, &mut cursor, size);
+
+		let mut source = String::new();
+		writeln!(source, "#[repr(C)]").unwrap();
+		if !self.derives.is_empty() {
+			writeln!(source, "#[derive({})]", self.derives.join(", ")).unwrap();
+		}
+		writeln!(source, "pub struct {name} {{").unwrap();
+		source.push_str(&body);
+		writeln!(source, "}}").unwrap();
+		writeln!(source).unwrap();
+		writeln!(
+			source,
+			"const _: () = assert!(::core::mem::size_of::<{name}>() == {size});"
+		)
+		.unwrap();
+		source.push_str(&asserts);
+		Ok(source)
+	}
+}
+
+/// Unwraps buffer and block types down to the layout of the data they hold.
+pub(super) fn data_layout(type_layout: &TypeLayout) -> Result<&TypeLayout, ReflectionError> {
+	let mut layout = type_layout;
+	loop {
+		layout = match layout.kind() {
+			TypeKind::ConstantBuffer
+			| TypeKind::ParameterBlock
+			| TypeKind::TextureBuffer
+			| TypeKind::ShaderStorageBuffer
+			| TypeKind::Resource => layout
+				.element_type_layout()
+				.ok_or(ReflectionError::UnexpectedNull)?,
+			_ => return Ok(layout),
+		};
+	}
+}
+
+fn scalar(layout: &TypeLayout) -> Result<(&'static str, usize), ReflectionError> {
+	let scalar_type = layout
+		.scalar_type()
+		.ok_or(ReflectionError::UnexpectedNull)?;
+	Ok(match scalar_type {
+		// Booleans occupy 32 bits in GPU memory.
+		ScalarType::Bool => ("u32", 4),
+		ScalarType::Int8 => ("i8", 1),
+		ScalarType::Uint8 => ("u8", 1),
+		ScalarType::Int16 => ("i16", 2),
+		ScalarType::Uint16 => ("u16", 2),
+		ScalarType::Int32 => ("i32", 4),
+		ScalarType::Uint32 => ("u32", 4),
+		ScalarType::Int64 => ("i64", 8),
+		ScalarType::Uint64 => ("u64", 8),
+		// Half floats are stored as their raw bits.
+		ScalarType::Float16 => ("u16", 2),
+		ScalarType::Float32 => ("f32", 4),
+		ScalarType::Float64 => ("f64", 8),
+		other => {
+			return Err(ReflectionError::TypeMismatch {
+				expected: "numeric scalar type".to_string(),
+				actual: format!("{other:?}"),
+			});
+		}
+	})
+}
+
+/// Maps a matrix to nested arrays in memory order.
+///
+/// Row-major matrices become `[[T; columns]; rows]` and column-major ones `[[T; rows]; columns]`. When the vectors are
+/// padded to a larger stride the inner arrays include the padding elements, and if the last vector is not padded the
+/// matrix is flattened to `[T; N]` instead.
+fn matrix(layout: &TypeLayout) -> Result<(String, usize, Option<String>), ReflectionError> {
+	let (ty, scalar_size) = scalar(layout)?;
+	let rows = layout.row_count().unwrap_or(0) as usize;
+	let columns = layout.column_count().unwrap_or(0) as usize;
+	let size = layout.size(UNIFORM);
+
+	let (major, minor, order) = match layout.matrix_layout_mode() {
+		MatrixLayoutMode::RowMajor => (rows, columns, "row-major"),
+		_ => (columns, rows, "column-major"),
+	};
+	let doc = |stride: usize| {
+		Some(format!(
+			"{order} {rows}x{columns} matrix, {stride} bytes per vector."
+		))
+	};
+
+	let stride = matrix_stride(size, major, minor, scalar_size).ok_or_else(|| {
+		ReflectionError::TypeMismatch {
+			expected: format!("{order} {rows}x{columns} matrix layout"),
+			actual: format!("{size} bytes"),
+		}
+	})?;
+	if stride * major == size {
+		Ok((
+			format!("[[{ty}; {}]; {major}]", stride / scalar_size),
+			size,
+			doc(stride),
+		))
+	} else {
+		// The last vector is not padded, so the vectors cannot be separate arrays.
+		Ok((format!("[{ty}; {}]", size / scalar_size), size, doc(stride)))
+	}
+}
+
+/// The distance in bytes between the `major` rows or columns of a matrix of `size` bytes, each holding `minor` scalars.
+///
+/// Layouts either pad every vector (std140, std430, Metal) or every vector but the last (D3D constant buffers), and
+/// both can explain the same size: a 24-byte D3D `float2x2` also fits two 12-byte vectors. A stride that divides the
+/// size evenly is therefore only taken when it keeps the vectors aligned.
+pub(super) fn matrix_stride(
+	size: usize,
+	major: usize,
+	minor: usize,
+	scalar_size: usize,
+) -> Option<usize> {
+	let tight = minor * scalar_size;
+	if size == major * tight {
+		return Some(tight);
+	}
+
+	if major > 0 && size.is_multiple_of(major * scalar_size) {
+		let stride = size / major;
+		if stride > tight && stride.is_multiple_of(tight.next_power_of_two()) {
+			return Some(stride);
+		}
+	}
+	if major > 1 && size > tight && (size - tight).is_multiple_of(major - 1) {
+		let stride = (size - tight) / (major - 1);
+		if stride > tight {
+			return Some(stride);
+		}
+	}
+	None
+}
+
+fn check_size(context: &str, actual: usize, expected: usize) -> Result<(), ReflectionError> {
+	if actual == expected {
+		Ok(())
+	} else {
+		Err(ReflectionError::TypeMismatch {
+			expected: format!("{expected} bytes for `{context}`"),
+			actual: format!("{actual} bytes"),
+		})
+	}
+}
+
+/// Turns a Slang type name such as `Light<float>` into a Rust identifier.
+fn identifier(name: &str) -> String {
+	let mut out: String = name
+		.chars()
+		.map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+		.collect();
+	while out.ends_with('_') && out.len() > 1 {
+		out.pop();
+	}
+	if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+		out.insert(0, '_');
+	}
+	out
+}
+
+/// Converts a Slang name to a Rust identifier, escaping keywords.
+pub(crate) fn field_identifier(name: &str) -> String {
+	const KEYWORDS: &[&str] = &[
+		"abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do",
+		"dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+		"let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+		"return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+		"unsized", "use", "virtual", "where", "while", "yield",
+	];
+
+	// These cannot be raw identifiers.
+	const RESERVED: &[&str] = &["crate", "self", "Self", "super"];
+
+	let name = identifier(name);
+	if KEYWORDS.contains(&name.as_str()) {
+		format!("r#{name}")
+	} else if RESERVED.contains(&name.as_str()) {
+		format!("{name}_")
+	} else {
+		name
+	}
+}
+
+fn pascal_case(name: &str) -> String {
+	identifier(name)
+		.split('_')
+		.filter(|s| !s.is_empty())
+		.map(|s| {
+			let mut chars = s.chars();
+			chars
+				.next()
+				.map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+				.unwrap_or_default()
+		})
+		.collect()
+}
@@ -0,0 +1,311 @@
+use super::{ReflectionError, TypeLayout, codegen, host_layout};
+use crate::{MatrixLayoutMode, ParameterCategory, ScalarType, TypeKind};
+
+const UNIFORM: ParameterCategory = ParameterCategory::Uniform;
+
+/// A scalar that can be written to uniform data.
+pub trait ShaderScalar: Copy {
+	const SCALAR_TYPE: ScalarType;
+	const SIZE: usize;
+
+	/// Writes the little-endian representation of `self` to the start of `out`.
+	fn write_to(self, out: &mut [u8]);
+}
+
+macro_rules! shader_scalar {
+	($($ty:ty => $scalar:ident),* $(,)?) => {
+		$(impl ShaderScalar for $ty {
+			const SCALAR_TYPE: ScalarType = ScalarType::$scalar;
+			const SIZE: usize = size_of::<$ty>();
+
+			fn write_to(self, out: &mut [u8]) {
+				out[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+			}
+		})*
+	};
+}
+
+shader_scalar! {
+	i8 => Int8,
+	u8 => Uint8,
+	i16 => Int16,
+	u16 => Uint16,
+	i32 => Int32,
+	u32 => Uint32,
+	i64 => Int64,
+	u64 => Uint64,
+	f32 => Float32,
+	f64 => Float64,
+}
+
+/// Writes uniform data into a byte buffer by following a [`TypeLayout`].
+///
+/// Fields and array elements are reached by name and index, so constant buffers can be filled without a host struct
+/// mirroring the shader type:
+///
+/// ```ignore
+/// let mut data = vec![0u8; type_layout.size(ParameterCategory::Uniform)];
+/// let mut cursor = ShaderCursor::new(&mut data, type_layout)?;
+/// cursor.path("lights[3].color")?.write_vec(&[1.0f32, 0.5, 0.0])?;
+/// cursor.field("exposure")?.write_f32(1.5)?;
+/// ```
+pub struct ShaderCursor<'a> {
+	data: &'a mut [u8],
+	layout: &'a TypeLayout,
+	offset: usize,
+}
+
+impl<'a> ShaderCursor<'a> {
+	/// Creates a cursor at the start of `data`.
+	///
+	/// Constant buffers, parameter blocks and structured buffers are unwrapped to their element layout, so the
+	/// layout of a constant buffer parameter can be passed directly.
+	pub fn new(data: &'a mut [u8], type_layout: &'a TypeLayout) -> Result<Self, ReflectionError> {
+		Ok(Self {
+			data,
+			layout: codegen::data_layout(type_layout)?,
+			offset: 0,
+		})
+	}
+
+	/// Byte offset of the cursor within the buffer.
+	pub fn offset(&self) -> usize {
+		self.offset
+	}
+
+	pub fn type_layout(&self) -> &'a TypeLayout {
+		self.layout
+	}
+
+	/// Returns a cursor to the field called `name`.
+	pub fn field(&mut self, name: &str) -> Result<ShaderCursor<'_>, ReflectionError> {
+		let (layout, offset) = self.field_location(name)?;
+		Ok(self.child(layout, offset))
+	}
+
+	/// Returns a cursor to element `index` of an array.
+	pub fn element(&mut self, index: usize) -> Result<ShaderCursor<'_>, ReflectionError> {
+		let (layout, offset) = self.element_location(index)?;
+		Ok(self.child(layout, offset))
+	}
+
+	/// Returns a cursor to the value at `path`, e.g. `"lights[3].color"`.
+	pub fn path(&mut self, path: &str) -> Result<ShaderCursor<'_>, ReflectionError> {
+		let mut layout = self.layout;
+		let mut offset = self.offset;
+
+		for segment in path.split('.') {
+			let (name, mut indices) = segment.split_once('[').unwrap_or((segment, ""));
+			if !name.is_empty() {
+				(layout, offset) = location(layout, offset).field_location(name)?;
+			}
+
+			while !indices.is_empty() {
+				let (index, rest) = indices.split_once(']').ok_or_else(|| invalid_path(path))?;
+				let index = index.trim().parse().map_err(|_| invalid_path(path))?;
+				(layout, offset) = location(layout, offset).element_location(index)?;
+
+				indices = match rest.strip_prefix('[') {
+					Some(rest) => rest,
+					None if rest.is_empty() => "",
+					None => return Err(invalid_path(path)),
+				};
+			}
+		}
+
+		Ok(self.child(layout, offset))
+	}
+
+	/// Writes raw bytes at the cursor.
+	pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<(), ReflectionError> {
+		self.bytes_mut(bytes.len())?.copy_from_slice(bytes);
+		Ok(())
+	}
+
+	/// Writes a scalar. Shader `bool` values accept 32-bit integers and `half` values accept `u16`.
+	pub fn write_scalar<T: ShaderScalar>(&mut self, value: T) -> Result<(), ReflectionError> {
+		self.expect_kind(TypeKind::Scalar)?;
+		self.expect_scalar::<T>()?;
+		value.write_to(self.bytes_mut(T::SIZE)?);
+		Ok(())
+	}
+
+	pub fn write_f32(&mut self, value: f32) -> Result<(), ReflectionError> {
+		self.write_scalar(value)
+	}
+
+	pub fn write_i32(&mut self, value: i32) -> Result<(), ReflectionError> {
+		self.write_scalar(value)
+	}
+
+	pub fn write_u32(&mut self, value: u32) -> Result<(), ReflectionError> {
+		self.write_scalar(value)
+	}
+
+	/// Writes a vector. The number of values must match the vector's element count.
+	pub fn write_vec<T: ShaderScalar>(&mut self, values: &[T]) -> Result<(), ReflectionError> {
+		self.expect_kind(TypeKind::Vector)?;
+		self.expect_scalar::<T>()?;
+
+		let count = self.layout.element_count().unwrap_or(0);
+		if values.len() != count {
+			return Err(ReflectionError::TypeMismatch {
+				expected: format!("{count} vector elements"),
+				actual: values.len().to_string(),
+			});
+		}
+
+		let bytes = self.bytes_mut(count * T::SIZE)?;
+		for (value, out) in values.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+			value.write_to(out);
+		}
+		Ok(())
+	}
+
+	/// Writes a matrix given as `R` rows of `C` columns.
+	///
+	/// The values are stored in the order given by the layout's [`MatrixLayoutMode`], with each row or column placed
+	/// at the vector stride of the layout, so padding between them is left untouched.
+	pub fn write_matrix<T: ShaderScalar, const R: usize, const C: usize>(
+		&mut self,
+		rows: &[[T; C]; R],
+	) -> Result<(), ReflectionError> {
+		self.expect_kind(TypeKind::Matrix)?;
+		self.expect_scalar::<T>()?;
+
+		let shader_rows = self.layout.row_count().unwrap_or(0) as usize;
+		let shader_columns = self.layout.column_count().unwrap_or(0) as usize;
+		if (R, C) != (shader_rows, shader_columns) {
+			return Err(ReflectionError::TypeMismatch {
+				expected: format!("{shader_rows}x{shader_columns} matrix"),
+				actual: format!("{R}x{C}"),
+			});
+		}
+
+		let row_major = self.layout.matrix_layout_mode() == MatrixLayoutMode::RowMajor;
+		let (major, minor) = if row_major { (R, C) } else { (C, R) };
+		let size = self.layout.size(UNIFORM);
+		let stride = codegen::matrix_stride(size, major, minor, T::SIZE).ok_or_else(|| {
+			ReflectionError::TypeMismatch {
+				expected: format!("{R}x{C} matrix layout"),
+				actual: format!("{size} bytes"),
+			}
+		})?;
+
+		let bytes = self.bytes_mut(size)?;
+		for (r, row) in rows.iter().enumerate() {
+			for (c, value) in row.iter().enumerate() {
+				let (i, j) = if row_major { (r, c) } else { (c, r) };
+				value.write_to(&mut bytes[i * stride + j * T::SIZE..]);
+			}
+		}
+		Ok(())
+	}
+
+	fn child(&mut self, layout: &'a TypeLayout, offset: usize) -> ShaderCursor<'_> {
+		ShaderCursor {
+			data: &mut *self.data,
+			layout,
+			offset,
+		}
+	}
+
+	fn field_location(&self, name: &str) -> Result<(&'a TypeLayout, usize), ReflectionError> {
+		location(self.layout, self.offset).field_location(name)
+	}
+
+	fn element_location(&self, index: usize) -> Result<(&'a TypeLayout, usize), ReflectionError> {
+		location(self.layout, self.offset).element_location(index)
+	}
+
+	fn expect_kind(&self, kind: TypeKind) -> Result<(), ReflectionError> {
+		let actual = self.layout.kind();
+		if actual == kind {
+			Ok(())
+		} else {
+			Err(ReflectionError::TypeMismatch {
+				expected: format!("{kind:?}"),
+				actual: format!("{actual:?}"),
+			})
+		}
+	}
+
+	fn expect_scalar<T: ShaderScalar>(&self) -> Result<(), ReflectionError> {
+		let actual = self.layout.scalar_type().unwrap_or(ScalarType::None);
+		if host_layout::scalar_compatible(T::SCALAR_TYPE, actual) {
+			Ok(())
+		} else {
+			Err(ReflectionError::TypeMismatch {
+				expected: format!("{actual:?}"),
+				actual: format!("{:?}", T::SCALAR_TYPE),
+			})
+		}
+	}
+
+	fn bytes_mut(&mut self, len: usize) -> Result<&mut [u8], ReflectionError> {
+		let required = self.offset.saturating_add(len);
+		let available = self.data.len();
+		self.data
+			.get_mut(self.offset..required)
+			.ok_or(ReflectionError::BufferTooSmall {
+				required,
+				len: available,
+			})
+	}
+}
+
+/// A layout and offset, used to resolve paths without borrowing the buffer.
+struct Location<'a> {
+	layout: &'a TypeLayout,
+	offset: usize,
+}
+
+fn location(layout: &TypeLayout, offset: usize) -> Location<'_> {
+	Location { layout, offset }
+}
+
+impl<'a> Location<'a> {
+	fn field_location(&self, name: &str) -> Result<(&'a TypeLayout, usize), ReflectionError> {
+		let index = self.layout.find_field_index_by_name(name);
+		let field = u32::try_from(index)
+			.ok()
+			.and_then(|i| self.layout.field_by_index(i))
+			.ok_or_else(|| ReflectionError::NotFound(format!("Field '{}'", name)))?;
+		let layout = field.type_layout().ok_or(ReflectionError::UnexpectedNull)?;
+		Ok((layout, self.offset.saturating_add(field.offset(UNIFORM))))
+	}
+
+	fn element_location(&self, index: usize) -> Result<(&'a TypeLayout, usize), ReflectionError> {
+		if self.layout.kind() != TypeKind::Array {
+			return Err(ReflectionError::TypeMismatch {
+				expected: "Array".to_string(),
+				actual: format!("{:?}", self.layout.kind()),
+			});
+		}
+
+		// Unbounded arrays report a count of zero or `usize::MAX`; their elements are only limited by the buffer.
+		let count = self.layout.element_count().unwrap_or(0);
+		if count != 0 && count != usize::MAX && index >= count {
+			return Err(ReflectionError::IndexOutOfBounds {
+				index: index as u32,
+				size: count as u32,
+			});
+		}
+
+		let layout = self
+			.layout
+			.element_type_layout()
+			.ok_or(ReflectionError::UnexpectedNull)?;
+		let stride = self.layout.element_stride(UNIFORM);
+		// An offset that overflows is past the end of any buffer, which writing reports as `BufferTooSmall`.
+		let offset = index
+			.checked_mul(stride)
+			.and_then(|offset| offset.checked_add(self.offset))
+			.unwrap_or(usize::MAX);
+		Ok((layout, offset))
+	}
+}
+
+fn invalid_path(path: &str) -> ReflectionError {
+	ReflectionError::NotFound(format!("Invalid path '{}'", path))
+}
@@ -1,4 +1,7 @@
-use super::{Function, TypeLayout, VariableLayout, rcall};
+use super::{
+	Function, RenderTargets, TypeLayout, VariableLayout, VertexAttribute, rcall, render_target,
+	vertex_input,
+};
 use crate::{Stage, sys};
 
 #[repr(transparent)]
@@ -28,6 +31,18 @@ impl EntryPoint {
 		})
 	}
 
+	/// Flattens the varying inputs of the entry point into vertex attributes, one per location.
+	///
+	/// Struct parameters are expanded field by field, and system values such as `SV_VertexID` are skipped.
+	pub fn vertex_inputs(&self) -> Vec<VertexAttribute> {
+		vertex_input::collect(self.parameters())
+	}
+
+	/// Reports the color and depth outputs of a fragment entry point. Other stages have none.
+	pub fn render_targets(&self) -> RenderTargets {
+		render_target::collect(self)
+	}
+
 	pub fn function(&self) -> Option<&Function> {
 		rcall!(spReflectionEntryPoint_getFunction(self) as Option<&Function>)
 	}
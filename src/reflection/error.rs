@@ -32,6 +32,10 @@ pub enum ReflectionError {
 	/// Item not found by name
 	#[error("Not found: {0}")]
 	NotFound(String),
+
+	/// A write would go past the end of the destination buffer
+	#[error("Buffer too small: {required} bytes required, {len} available")]
+	BufferTooSmall { required: usize, len: usize },
 }
 
 /// Errors specific to attribute value deserialization
@@ -0,0 +1,427 @@
+use super::{TypeLayout, VariableLayout, codegen};
+use crate::{MatrixLayoutMode, ParameterCategory, ScalarType, TypeKind};
+use thiserror::Error;
+
+const UNIFORM: ParameterCategory = ParameterCategory::Uniform;
+
+/// Describes the memory layout of a host type mirroring shader data.
+///
+/// Implemented for the scalar types, for arrays of implementing types, and for structs via
+/// `#[derive(ShaderLayout)]`. Use [`check_layout`] to compare an implementation against reflection.
+///
+/// ```ignore
+/// #[derive(shader_slang::ShaderLayout)]
+/// #[repr(C)]
+/// struct Light {
+///     position: [f32; 3],
+///     intensity: f32,
+///     #[slang(name = "colorTint")]
+///     color_tint: [f32; 4],
+///     _pad0: [u8; 12], // fields starting with `_` are not compared
+/// }
+///
+/// let mismatches = check_layout::<Light>(type_layout);
+/// assert!(mismatches.is_empty(), "{mismatches:#?}");
+/// ```
+pub trait ShaderLayout {
+	fn host_type() -> HostType;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HostType {
+	Scalar(ScalarType),
+	Array {
+		element: Box<HostType>,
+		count: usize,
+		stride: usize,
+	},
+	Struct {
+		name: &'static str,
+		size: usize,
+		fields: Vec<HostField>,
+	},
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostField {
+	pub name: &'static str,
+	pub offset: usize,
+	pub size: usize,
+	pub ty: HostType,
+}
+
+macro_rules! scalar_layout {
+	($($ty:ty => $scalar:ident),* $(,)?) => {
+		$(impl ShaderLayout for $ty {
+			fn host_type() -> HostType {
+				HostType::Scalar(ScalarType::$scalar)
+			}
+		})*
+	};
+}
+
+scalar_layout! {
+	bool => Bool,
+	i8 => Int8,
+	u8 => Uint8,
+	i16 => Int16,
+	u16 => Uint16,
+	i32 => Int32,
+	u32 => Uint32,
+	i64 => Int64,
+	u64 => Uint64,
+	f32 => Float32,
+	f64 => Float64,
+}
+
+impl<T: ShaderLayout, const N: usize> ShaderLayout for [T; N] {
+	fn host_type() -> HostType {
+		HostType::Array {
+			element: Box::new(T::host_type()),
+			count: N,
+			stride: size_of::<T>(),
+		}
+	}
+}
+
+/// A difference between a host type and the shader layout it mirrors.
+#[derive(Debug, Error, Clone, PartialEq)]
+#[error("`{path}`: {kind}")]
+pub struct LayoutMismatch {
+	/// Dotted path to the value, e.g. `lights[].color`.
+	pub path: String,
+	pub kind: LayoutMismatchKind,
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum LayoutMismatchKind {
+	/// The shader has a field that the host type lacks.
+	#[error("missing from the host type")]
+	MissingField,
+
+	/// The host type has a field that the shader lacks.
+	#[error("not present in the shader")]
+	UnknownField,
+
+	#[error("host offset {host}, shader offset {shader}")]
+	Offset { host: usize, shader: usize },
+
+	#[error("host size {host}, shader size {shader}")]
+	Size { host: usize, shader: usize },
+
+	#[error("host stride {host}, shader stride {shader}")]
+	Stride { host: usize, shader: usize },
+
+	#[error("host scalar type {host:?}, shader scalar type {shader:?}")]
+	ScalarType {
+		host: ScalarType,
+		shader: ScalarType,
+	},
+
+	#[error("host shape {host_rows}x{host_columns}, shader shape {shader_rows}x{shader_columns}")]
+	Shape {
+		host_rows: usize,
+		host_columns: usize,
+		shader_rows: usize,
+		shader_columns: usize,
+	},
+
+	#[error("host element count {host}, shader element count {shader}")]
+	ElementCount { host: usize, shader: usize },
+
+	#[error("host {host} cannot represent shader {shader:?}")]
+	Kind {
+		host: &'static str,
+		shader: TypeKind,
+	},
+}
+
+/// Compares `T` against the [`ParameterCategory::Uniform`] layout of `type_layout` and returns every mismatch.
+///
+/// Constant buffers, parameter blocks and structured buffers are unwrapped to their element layout. Host fields whose
+/// name starts with `_` are treated as padding. Shader `bool` matches 32-bit host integers and `half` matches `u16`,
+/// since Rust has no types with the same layout. The padded array wrappers emitted by
+/// [`codegen`](super::codegen) are recognised and compared element by element.
+pub fn check_layout<T: ShaderLayout>(type_layout: &TypeLayout) -> Vec<LayoutMismatch> {
+	let mut mismatches = Vec::new();
+	match codegen::data_layout(type_layout) {
+		Ok(layout) => compare(String::new(), &T::host_type(), layout, &mut mismatches),
+		Err(_) => mismatches.push(LayoutMismatch {
+			path: String::new(),
+			kind: LayoutMismatchKind::Kind {
+				host: host_kind(&T::host_type()),
+				shader: type_layout.kind(),
+			},
+		}),
+	}
+	mismatches
+}
+
+/// Like [`check_layout`], using the type layout of `variable_layout`.
+pub fn check_variable_layout<T: ShaderLayout>(
+	variable_layout: &VariableLayout,
+) -> Vec<LayoutMismatch> {
+	match variable_layout.type_layout() {
+		Some(type_layout) => check_layout::<T>(type_layout),
+		None => vec![LayoutMismatch {
+			path: variable_layout.name().unwrap_or_default().to_string(),
+			kind: LayoutMismatchKind::Kind {
+				host: host_kind(&T::host_type()),
+				shader: TypeKind::None,
+			},
+		}],
+	}
+}
+
+fn compare(path: String, host: &HostType, shader: &TypeLayout, out: &mut Vec<LayoutMismatch>) {
+	match (shader.kind(), host) {
+		(TypeKind::Struct, HostType::Struct { fields, .. }) => {
+			let fields = fields
+				.iter()
+				.filter(|f| !f.name.starts_with('_'))
+				.collect::<Vec<_>>();
+			let mut matched = vec![false; fields.len()];
+
+			for field in shader.fields() {
+				let Some(field_layout) = field.type_layout() else {
+					continue;
+				};
+				let size = field_layout.size(UNIFORM);
+				if size == 0 {
+					continue;
+				}
+
+				let name = field.name().unwrap_or_default();
+				let field_path = join(&path, name);
+				let Some(index) = fields.iter().position(|f| f.name == name) else {
+					push(out, &field_path, LayoutMismatchKind::MissingField);
+					continue;
+				};
+				matched[index] = true;
+
+				let host_field = fields[index];
+				let offset = field.offset(UNIFORM);
+				if host_field.offset != offset {
+					push(
+						out,
+						&field_path,
+						LayoutMismatchKind::Offset {
+							host: host_field.offset,
+							shader: offset,
+						},
+					);
+				}
+				if host_field.size != size {
+					push(
+						out,
+						&field_path,
+						LayoutMismatchKind::Size {
+							host: host_field.size,
+							shader: size,
+						},
+					);
+				}
+				compare(field_path, &host_field.ty, field_layout, out);
+			}
+
+			for (field, matched) in fields.iter().zip(matched) {
+				if !matched {
+					out.push(LayoutMismatch {
+						path: join(&path, field.name),
+						kind: LayoutMismatchKind::UnknownField,
+					});
+				}
+			}
+		}
+		(TypeKind::Array, host) => match array_parts(host) {
+			Some((element, count, stride)) => {
+				let shader_count = shader.element_count().unwrap_or(0);
+				if count != shader_count {
+					push(
+						out,
+						&path,
+						LayoutMismatchKind::ElementCount {
+							host: count,
+							shader: shader_count,
+						},
+					);
+				}
+				let shader_stride = shader.element_stride(UNIFORM);
+				if stride != shader_stride {
+					push(
+						out,
+						&path,
+						LayoutMismatchKind::Stride {
+							host: stride,
+							shader: shader_stride,
+						},
+					);
+				}
+				if let Some(element_layout) = shader.element_type_layout() {
+					compare(format!("{path}[]"), element, element_layout, out);
+				}
+			}
+			None => push(
+				out,
+				&path,
+				LayoutMismatchKind::Kind {
+					host: host_kind(host),
+					shader: TypeKind::Array,
+				},
+			),
+		},
+		(kind @ (TypeKind::Scalar | TypeKind::Vector | TypeKind::Matrix), host) => {
+			let Some((host_scalar, outer, inner)) = numeric_shape(host) else {
+				push(
+					out,
+					&path,
+					LayoutMismatchKind::Kind {
+						host: host_kind(host),
+						shader: kind,
+					},
+				);
+				return;
+			};
+
+			let shader_scalar = shader.scalar_type().unwrap_or(ScalarType::None);
+			if !scalar_compatible(host_scalar, shader_scalar) {
+				push(
+					out,
+					&path,
+					LayoutMismatchKind::ScalarType {
+						host: host_scalar,
+						shader: shader_scalar,
+					},
+				);
+			}
+
+			let rows = shader.row_count().unwrap_or(1) as usize;
+			let columns = shader.column_count().unwrap_or(1) as usize;
+			let (host_rows, host_columns) = match kind {
+				TypeKind::Scalar => (outer.max(1), inner),
+				TypeKind::Vector => (1, outer.max(1) * inner),
+				// Flattened matrices carry no shape, so only the size check applies to them.
+				_ if outer == 0 => (rows, columns),
+				_ => match shader.matrix_layout_mode() {
+					MatrixLayoutMode::RowMajor => (outer, inner.min(columns)),
+					_ => (inner.min(rows), outer),
+				},
+			};
+			let (shader_rows, shader_columns) = match kind {
+				TypeKind::Scalar => (1, 1),
+				TypeKind::Vector => (1, shader.element_count().unwrap_or(columns)),
+				_ => (rows, columns),
+			};
+			if (host_rows, host_columns) != (shader_rows, shader_columns) {
+				push(
+					out,
+					&path,
+					LayoutMismatchKind::Shape {
+						host_rows,
+						host_columns,
+						shader_rows,
+						shader_columns,
+					},
+				);
+			}
+		}
+		(kind, host) => push(
+			out,
+			&path,
+			LayoutMismatchKind::Kind {
+				host: host_kind(host),
+				shader: kind,
+			},
+		),
+	}
+}
+
+fn push(out: &mut Vec<LayoutMismatch>, path: &str, kind: LayoutMismatchKind) {
+	out.push(LayoutMismatch {
+		path: path.to_string(),
+		kind,
+	});
+}
+
+/// Returns the element, count and stride of a host array, looking through codegen's padded wrappers.
+fn array_parts(host: &HostType) -> Option<(&HostType, usize, usize)> {
+	match host {
+		HostType::Array {
+			element,
+			count,
+			stride,
+		} => Some((unpad(element), *count, *stride)),
+		// `{ elements: [Padded; N - 1], last: T }`, used when the last element has no trailing padding.
+		HostType::Struct { fields, .. } => {
+			let [elements, last] = fields.as_slice() else {
+				return None;
+			};
+			if elements.name != "elements" || last.name != "last" {
+				return None;
+			}
+			let (element, count, stride) = array_parts(&elements.ty)?;
+			Some((element, count + 1, stride))
+		}
+		HostType::Scalar(_) => None,
+	}
+}
+
+/// Looks through `{ value: T, _pad: [u8; N] }` element wrappers.
+fn unpad(host: &HostType) -> &HostType {
+	if let HostType::Struct { fields, .. } = host
+		&& let [value, rest @ ..] = fields.as_slice()
+		&& value.name == "value"
+		&& value.offset == 0
+		&& rest.iter().all(|f| f.name.starts_with('_'))
+	{
+		&value.ty
+	} else {
+		host
+	}
+}
+
+/// Returns the scalar type and the outer and inner element counts of a scalar, vector or matrix mirror.
+///
+/// Scalars are `(1, 1)`, `[T; N]` is `(1, N)` and `[[T; M]; N]` is `(N, M)`. A flat `[T; N]` standing in for a
+/// matrix is `(0, N)`.
+fn numeric_shape(host: &HostType) -> Option<(ScalarType, usize, usize)> {
+	match host {
+		HostType::Scalar(scalar) => Some((*scalar, 1, 1)),
+		HostType::Array { element, count, .. } => match element.as_ref() {
+			HostType::Scalar(scalar) => Some((*scalar, 0, *count)),
+			HostType::Array {
+				element, count: m, ..
+			} => match element.as_ref() {
+				HostType::Scalar(scalar) => Some((*scalar, *count, *m)),
+				_ => None,
+			},
+			HostType::Struct { .. } => None,
+		},
+		HostType::Struct { .. } => None,
+	}
+}
+
+pub(super) fn scalar_compatible(host: ScalarType, shader: ScalarType) -> bool {
+	host == shader
+		|| matches!(
+			(host, shader),
+			(ScalarType::Uint32 | ScalarType::Int32, ScalarType::Bool)
+				| (ScalarType::Uint16, ScalarType::Float16)
+		)
+}
+
+fn host_kind(host: &HostType) -> &'static str {
+	match host {
+		HostType::Scalar(_) => "scalar",
+		HostType::Array { .. } => "array",
+		HostType::Struct { .. } => "struct",
+	}
+}
+
+fn join(path: &str, name: &str) -> String {
+	if path.is_empty() {
+		name.to_string()
+	} else {
+		format!("{path}.{name}")
+	}
+}
@@ -0,0 +1,787 @@
+//! Reflection JSON in the format written by `slangc -reflection-json`.
+//!
+//! The output mirrors the schema of `slangc` so that tools consuming its files keep working. Loading such a file back
+//! yields a [`snapshot::Shader`] holding everything the JSON describes; data `slangc` does not emit, e.g. descriptor
+//! set layouts, is left empty.
+
+use serde_json::{Map, Value, json};
+
+use super::ReflectionError;
+use super::snapshot::{
+	self, AttributeArgument, EntryPoint, HashedString, Offset, RESOURCE_SHAPE_ARRAY_FLAG,
+	RESOURCE_SHAPE_FEEDBACK_FLAG, RESOURCE_SHAPE_MULTISAMPLE_FLAG, Size, Type, TypeLayout,
+	UserAttribute, VariableLayout,
+};
+use crate::{MatrixLayoutMode, ParameterCategory, ResourceAccess, ScalarType, Stage, TypeKind};
+
+/// Marks the size of unbounded arrays, i.e. `SLANG_UNBOUNDED_SIZE`.
+const UNBOUNDED_SIZE: usize = usize::MAX;
+
+const CATEGORIES: &[(ParameterCategory, &str)] = &[
+	(ParameterCategory::ConstantBuffer, "constantBuffer"),
+	(ParameterCategory::ShaderResource, "shaderResource"),
+	(ParameterCategory::UnorderedAccess, "unorderedAccess"),
+	(ParameterCategory::VaryingInput, "varyingInput"),
+	(ParameterCategory::VaryingOutput, "varyingOutput"),
+	(ParameterCategory::SamplerState, "samplerState"),
+	(ParameterCategory::Uniform, "uniform"),
+	(ParameterCategory::PushConstantBuffer, "pushConstantBuffer"),
+	(
+		ParameterCategory::DescriptorTableSlot,
+		"descriptorTableSlot",
+	),
+	(
+		ParameterCategory::SpecializationConstant,
+		"specializationConstant",
+	),
+	(ParameterCategory::Mixed, "mixed"),
+	(ParameterCategory::RegisterSpace, "registerSpace"),
+	(
+		ParameterCategory::SubElementRegisterSpace,
+		"subElementRegisterSpace",
+	),
+	(ParameterCategory::Generic, "generic"),
+	(ParameterCategory::RayPayload, "rayPayload"),
+	(ParameterCategory::HitAttributes, "hitAttributes"),
+	(ParameterCategory::CallablePayload, "callablePayload"),
+	(ParameterCategory::ShaderRecord, "shaderRecord"),
+	(
+		ParameterCategory::ExistentialTypeParam,
+		"existentialTypeParam",
+	),
+	(
+		ParameterCategory::ExistentialObjectParam,
+		"existentialObjectParam",
+	),
+	(ParameterCategory::Subpass, "subpass"),
+	(
+		ParameterCategory::MetalArgumentBufferElement,
+		"metalArgumentBufferElement",
+	),
+	(ParameterCategory::MetalAttribute, "metalAttribute"),
+	(ParameterCategory::MetalPayload, "metalPayload"),
+];
+
+const STAGES: &[(Stage, &str)] = &[
+	(Stage::Vertex, "vertex"),
+	(Stage::Hull, "hull"),
+	(Stage::Domain, "domain"),
+	(Stage::Geometry, "geometry"),
+	(Stage::Fragment, "fragment"),
+	(Stage::Compute, "compute"),
+	(Stage::RayGeneration, "raygeneration"),
+	(Stage::Intersection, "intersection"),
+	(Stage::AnyHit, "anyhit"),
+	(Stage::ClosestHit, "closesthit"),
+	(Stage::Miss, "miss"),
+	(Stage::Callable, "callable"),
+	(Stage::Mesh, "mesh"),
+	(Stage::Amplification, "amplification"),
+];
+
+const SCALAR_TYPES: &[(ScalarType, &str)] = &[
+	(ScalarType::Void, "void"),
+	(ScalarType::Bool, "bool"),
+	(ScalarType::Int32, "int32"),
+	(ScalarType::Uint32, "uint32"),
+	(ScalarType::Int64, "int64"),
+	(ScalarType::Uint64, "uint64"),
+	(ScalarType::Float16, "float16"),
+	(ScalarType::Float32, "float32"),
+	(ScalarType::Float64, "float64"),
+	(ScalarType::Int8, "int8"),
+	(ScalarType::Uint8, "uint8"),
+	(ScalarType::Int16, "int16"),
+	(ScalarType::Uint16, "uint16"),
+];
+
+const ACCESSES: &[(ResourceAccess, &str)] = &[
+	(ResourceAccess::Write, "write"),
+	(ResourceAccess::ReadWrite, "readWrite"),
+	(ResourceAccess::RasterOrdered, "rasterOrdered"),
+	(ResourceAccess::Append, "append"),
+	(ResourceAccess::Consume, "consume"),
+	(ResourceAccess::Feedback, "feedback"),
+];
+
+/// Base resource shapes by their numeric value, see [`snapshot::RESOURCE_BASE_SHAPE_MASK`].
+const BASE_SHAPES: &[(u32, &str)] = &[
+	(1, "texture1D"),
+	(2, "texture2D"),
+	(3, "texture3D"),
+	(4, "textureCube"),
+	(5, "textureBuffer"),
+	(6, "structuredBuffer"),
+	(7, "byteAddressBuffer"),
+	(9, "accelerationStructure"),
+	(10, "textureSubpass"),
+];
+
+const STRUCTURED_BUFFER: u32 = 6;
+
+const PARAMETER_GROUP_KINDS: &[(TypeKind, &str)] = &[
+	(TypeKind::ConstantBuffer, "constantBuffer"),
+	(TypeKind::ParameterBlock, "parameterBlock"),
+	(TypeKind::TextureBuffer, "textureBuffer"),
+	(TypeKind::ShaderStorageBuffer, "shaderStorageBuffer"),
+];
+
+fn name_of<T: PartialEq + Copy>(table: &[(T, &'static str)], value: T) -> Option<&'static str> {
+	table.iter().find(|(v, _)| *v == value).map(|(_, n)| *n)
+}
+
+fn value_of<T: Copy>(table: &[(T, &'static str)], name: &str) -> Option<T> {
+	table.iter().find(|(_, n)| *n == name).map(|(v, _)| *v)
+}
+
+impl super::Shader {
+	/// Writes the reflection data in the same JSON format as `slangc -reflection-json`.
+	pub fn to_reflection_json(&self) -> String {
+		self.snapshot().to_reflection_json()
+	}
+}
+
+impl snapshot::Shader {
+	/// Writes the reflection data in the same JSON format as `slangc -reflection-json`.
+	pub fn to_reflection_json(&self) -> String {
+		let mut root = Map::new();
+		root.insert(
+			"parameters".into(),
+			self.parameters.iter().map(param_json).collect(),
+		);
+		root.insert(
+			"entryPoints".into(),
+			self.entry_points.iter().map(entry_point_json).collect(),
+		);
+		if !self.hashed_strings.is_empty() {
+			root.insert(
+				"hashedStrings".into(),
+				self.hashed_strings
+					.iter()
+					.map(|s| (s.string.clone(), json!(s.hash)))
+					.collect::<Map<_, _>>()
+					.into(),
+			);
+		}
+
+		let mut out = Vec::new();
+		let formatter = serde_json::ser::PrettyFormatter::with_indent(b"    ");
+		let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+		serde::Serialize::serialize(&Value::Object(root), &mut serializer)
+			.expect("serializing a JSON value into memory cannot fail");
+		String::from_utf8(out).expect("serde_json always produces valid UTF-8")
+	}
+
+	/// Loads reflection JSON as written by `slangc -reflection-json` or [`to_reflection_json`](Self::to_reflection_json).
+	pub fn from_reflection_json(json: &str) -> Result<Self, ReflectionError> {
+		let root: Value = serde_json::from_str(json)
+			.map_err(|e| ReflectionError::DeserializationError(e.to_string()))?;
+
+		Ok(Self {
+			parameters: objects(&root, "parameters")?
+				.map(var_layout_from_json)
+				.collect::<Result<_, _>>()?,
+			entry_points: objects(&root, "entryPoints")?
+				.map(entry_point_from_json)
+				.collect::<Result<_, _>>()?,
+			global_constant_buffer_binding: 0,
+			global_constant_buffer_size: 0,
+			hashed_strings: root
+				.get("hashedStrings")
+				.and_then(Value::as_object)
+				.into_iter()
+				.flatten()
+				.map(|(string, hash)| {
+					let hash = hash
+						.as_u64()
+						.and_then(|h| u32::try_from(h).ok())
+						.ok_or_else(|| error(format!("invalid hash of string `{string}`")))?;
+					Ok::<_, ReflectionError>(HashedString {
+						string: string.clone(),
+						hash,
+					})
+				})
+				.collect::<Result<_, _>>()?,
+		})
+	}
+}
+
+fn entry_point_json(entry_point: &EntryPoint) -> Value {
+	let mut map = Map::new();
+	if let Some(name) = &entry_point.name {
+		map.insert("name".into(), json!(name));
+	}
+	if let Some(stage) = name_of(STAGES, entry_point.stage) {
+		map.insert("stage".into(), json!(stage));
+	}
+	if !entry_point.parameters.is_empty() {
+		map.insert(
+			"parameters".into(),
+			entry_point.parameters.iter().map(param_json).collect(),
+		);
+	}
+	if entry_point.uses_any_sample_rate_input {
+		map.insert("usesAnySampleRateInput".into(), json!(true));
+	}
+	if let Some(result) = &entry_point.result {
+		// The spelling including the colon is what slangc writes.
+		map.insert("result:".into(), param_json(result));
+	}
+	if entry_point.stage == Stage::Compute {
+		map.insert(
+			"threadGroupSize".into(),
+			json!(entry_point.compute_thread_group_size),
+		);
+	}
+	insert_user_attributes(&mut map, &entry_point.user_attributes);
+	map.into()
+}
+
+/// A top-level or entry point parameter.
+fn param_json(param: &VariableLayout) -> Value {
+	let mut map = Map::new();
+	if let Some(name) = &param.name {
+		map.insert("name".into(), json!(name));
+	}
+	insert_binding_info(&mut map, param);
+	map.insert("type".into(), type_layout_json(&param.type_layout));
+	insert_user_attributes(&mut map, &param.user_attributes);
+	map.into()
+}
+
+/// A field of a struct or the element of a parameter group.
+fn var_layout_json(var: &VariableLayout) -> Value {
+	let mut map = Map::new();
+	if let Some(name) = &var.name {
+		map.insert("name".into(), json!(name));
+	}
+	map.insert("type".into(), type_layout_json(&var.type_layout));
+	insert_binding_info(&mut map, var);
+	insert_user_attributes(&mut map, &var.user_attributes);
+	map.into()
+}
+
+fn insert_binding_info(map: &mut Map<String, Value>, var: &VariableLayout) {
+	if let Some(stage) = name_of(STAGES, var.stage) {
+		map.insert("stage".into(), json!(stage));
+	}
+
+	let bindings = var
+		.offsets
+		.iter()
+		.map(|offset| binding_json(offset, var.type_layout.size(offset.category)))
+		.collect::<Vec<_>>();
+	match bindings.len() {
+		0 => {}
+		1 => {
+			map.insert("binding".into(), bindings.into_iter().next().unwrap());
+		}
+		_ => {
+			map.insert("bindings".into(), bindings.into());
+		}
+	}
+
+	if let Some(semantic_name) = &var.semantic_name {
+		map.insert("semanticName".into(), json!(semantic_name));
+		if var.semantic_index != 0 {
+			map.insert("semanticIndex".into(), json!(var.semantic_index));
+		}
+	}
+}
+
+fn binding_json(offset: &Offset, count: usize) -> Value {
+	let mut map = Map::new();
+	map.insert(
+		"kind".into(),
+		json!(name_of(CATEGORIES, offset.category).unwrap_or("unknown")),
+	);
+
+	if offset.category == ParameterCategory::Uniform {
+		map.insert("offset".into(), json!(offset.offset));
+		map.insert("size".into(), json!(count));
+	} else {
+		if offset.space != 0 && offset.category != ParameterCategory::RegisterSpace {
+			map.insert("space".into(), json!(offset.space));
+		}
+		map.insert("index".into(), json!(offset.offset));
+		if count != 1 {
+			map.insert(
+				"count".into(),
+				if count == UNBOUNDED_SIZE {
+					json!("unbounded")
+				} else {
+					json!(count)
+				},
+			);
+		}
+	}
+
+	map.into()
+}
+
+fn type_layout_json(type_layout: &TypeLayout) -> Value {
+	let mut map = Map::new();
+
+	match type_layout.kind {
+		TypeKind::Struct => {
+			map.insert("kind".into(), json!("struct"));
+			if let Some(name) = type_layout.name() {
+				map.insert("name".into(), json!(name));
+			}
+			map.insert(
+				"fields".into(),
+				type_layout.fields.iter().map(var_layout_json).collect(),
+			);
+		}
+		TypeKind::Array => {
+			map.insert("kind".into(), json!("array"));
+			map.insert(
+				"elementCount".into(),
+				json!(type_layout.ty.as_ref().map_or(0, |t| t.element_count)),
+			);
+			if let Some(element) = type_layout.element() {
+				map.insert("elementType".into(), type_layout_json(element));
+				if type_layout.size(ParameterCategory::Uniform) != 0 {
+					map.insert(
+						"uniformStride".into(),
+						json!(element.stride(ParameterCategory::Uniform)),
+					);
+				}
+			}
+		}
+		TypeKind::Pointer => {
+			map.insert("kind".into(), json!("pointer"));
+			let value_type = type_layout
+				.ty
+				.as_ref()
+				.and_then(|t| t.element_type.as_ref())
+				.and_then(|t| t.name.as_deref());
+			map.insert("valueType".into(), json!(value_type));
+		}
+		kind if name_of(PARAMETER_GROUP_KINDS, kind).is_some() => {
+			map.insert("kind".into(), json!(name_of(PARAMETER_GROUP_KINDS, kind)));
+			if let Some(element) = type_layout.element() {
+				map.insert("elementType".into(), type_layout_json(element));
+			}
+			if let Some(container) = &type_layout.container_var_layout {
+				let mut container_map = Map::new();
+				insert_binding_info(&mut container_map, container);
+				map.insert("containerVarLayout".into(), container_map.into());
+			}
+			if let Some(element) = &type_layout.element_var_layout {
+				map.insert("elementVarLayout".into(), var_layout_json(element));
+			}
+		}
+		TypeKind::Resource
+			if type_layout
+				.ty
+				.as_ref()
+				.is_some_and(|t| t.resource_shape as u32 == STRUCTURED_BUFFER) =>
+		{
+			insert_resource_info(&mut map, type_layout.ty.as_ref().unwrap());
+			if let Some(element) = type_layout.element() {
+				map.insert("resultType".into(), type_layout_json(element));
+			}
+		}
+		_ => {
+			if let Some(ty) = &type_layout.ty {
+				return type_json(ty);
+			}
+		}
+	}
+
+	if let Some(ty) = &type_layout.ty {
+		insert_user_attributes(&mut map, &ty.user_attributes);
+	}
+	map.into()
+}
+
+fn type_json(ty: &Type) -> Value {
+	let mut map = Map::new();
+
+	match ty.kind {
+		TypeKind::SamplerState => {
+			map.insert("kind".into(), json!("samplerState"));
+		}
+		TypeKind::Resource => {
+			insert_resource_info(&mut map, ty);
+			if let Some(result) = &ty.resource_result_type {
+				map.insert("resultType".into(), type_json(result));
+			}
+		}
+		kind if name_of(PARAMETER_GROUP_KINDS, kind).is_some() => {
+			map.insert("kind".into(), json!(name_of(PARAMETER_GROUP_KINDS, kind)));
+			if let Some(element) = &ty.element_type {
+				map.insert("elementType".into(), type_json(element));
+			}
+		}
+		TypeKind::Scalar => {
+			map.insert("kind".into(), json!("scalar"));
+			map.insert(
+				"scalarType".into(),
+				json!(name_of(SCALAR_TYPES, ty.scalar_type).unwrap_or("unknown")),
+			);
+		}
+		TypeKind::Vector => {
+			map.insert("kind".into(), json!("vector"));
+			map.insert("elementCount".into(), json!(ty.element_count));
+			if let Some(element) = &ty.element_type {
+				map.insert("elementType".into(), type_json(element));
+			}
+		}
+		TypeKind::Matrix => {
+			map.insert("kind".into(), json!("matrix"));
+			map.insert("rowCount".into(), json!(ty.row_count));
+			map.insert("columnCount".into(), json!(ty.column_count));
+			if let Some(element) = &ty.element_type {
+				map.insert("elementType".into(), type_json(element));
+			}
+		}
+		TypeKind::Array => {
+			map.insert("kind".into(), json!("array"));
+			map.insert("elementCount".into(), json!(ty.element_count));
+			if let Some(element) = &ty.element_type {
+				map.insert("elementType".into(), type_json(element));
+			}
+		}
+		TypeKind::Pointer => {
+			map.insert("kind".into(), json!("pointer"));
+			map.insert(
+				"valueType".into(),
+				json!(ty.element_type.as_ref().and_then(|t| t.name.as_deref())),
+			);
+		}
+		TypeKind::Struct => {
+			// Field types are only recorded as part of type layouts.
+			map.insert("kind".into(), json!("struct"));
+			map.insert("name".into(), json!(ty.name));
+			map.insert("fields".into(), json!([]));
+		}
+		TypeKind::GenericTypeParameter => {
+			map.insert("kind".into(), json!("GenericTypeParameter"));
+			map.insert("name".into(), json!(ty.name));
+		}
+		TypeKind::Interface => {
+			map.insert("kind".into(), json!("Interface"));
+			map.insert("name".into(), json!(ty.name));
+		}
+		TypeKind::Feedback => {
+			map.insert("kind".into(), json!("feedback"));
+			map.insert("name".into(), json!(ty.name));
+		}
+		TypeKind::DynamicResource => {
+			map.insert("kind".into(), json!("DynamicResource"));
+		}
+		_ => {
+			map.insert("kind".into(), json!("unknown"));
+		}
+	}
+
+	insert_user_attributes(&mut map, &ty.user_attributes);
+	map.into()
+}
+
+fn insert_resource_info(map: &mut Map<String, Value>, ty: &Type) {
+	map.insert("kind".into(), json!("resource"));
+	map.insert(
+		"baseShape".into(),
+		json!(name_of(BASE_SHAPES, ty.resource_shape as u32).unwrap_or("unknown")),
+	);
+	for (flag, name) in [
+		(RESOURCE_SHAPE_ARRAY_FLAG, "array"),
+		(RESOURCE_SHAPE_MULTISAMPLE_FLAG, "multisample"),
+		(RESOURCE_SHAPE_FEEDBACK_FLAG, "feedback"),
+	] {
+		if ty.resource_shape_flags & flag != 0 {
+			map.insert(name.into(), json!(true));
+		}
+	}
+	if ty.resource_access != ResourceAccess::Read {
+		map.insert(
+			"access".into(),
+			json!(name_of(ACCESSES, ty.resource_access).unwrap_or("unknown")),
+		);
+	}
+}
+
+fn insert_user_attributes(map: &mut Map<String, Value>, attributes: &[UserAttribute]) {
+	if attributes.is_empty() {
+		return;
+	}
+
+	let attributes = attributes
+		.iter()
+		.map(|attribute| {
+			let mut map = Map::new();
+			map.insert("name".into(), json!(attribute.name));
+			if !attribute.arguments.is_empty() {
+				map.insert(
+					"arguments".into(),
+					attribute
+						.arguments
+						.iter()
+						.map(|argument| match argument {
+							AttributeArgument::Int(value) => json!(value),
+							AttributeArgument::Float(value) => json!(value),
+							AttributeArgument::String(value) => json!(value),
+							AttributeArgument::Unknown => json!("invalid value"),
+						})
+						.collect(),
+				);
+			}
+			Value::Object(map)
+		})
+		.collect();
+	map.insert("userAttribs".into(), attributes);
+}
+
+fn error(message: impl Into<String>) -> ReflectionError {
+	ReflectionError::DeserializationError(message.into())
+}
+
+/// Iterates over the objects in the optional array `key` of `value`.
+fn objects<'a>(
+	value: &'a Value,
+	key: &str,
+) -> Result<impl Iterator<Item = &'a Value>, ReflectionError> {
+	let items = match value.get(key) {
+		None => &[][..],
+		Some(Value::Array(items)) => items.as_slice(),
+		Some(_) => return Err(error(format!("'{key}' must be an array"))),
+	};
+	Ok(items.iter())
+}
+
+fn string(value: &Value, key: &str) -> Option<String> {
+	value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn number(value: &Value, key: &str) -> Option<u64> {
+	value.get(key).and_then(Value::as_u64)
+}
+
+fn entry_point_from_json(value: &Value) -> Result<EntryPoint, ReflectionError> {
+	let thread_group_size = match value.get("threadGroupSize") {
+		Some(size) => serde_json::from_value(size.clone())
+			.map_err(|e| error(format!("invalid 'threadGroupSize': {e}")))?,
+		None => [0; 3],
+	};
+	let result = value.get("result:").or_else(|| value.get("result"));
+
+	Ok(EntryPoint {
+		name: string(value, "name"),
+		name_override: None,
+		stage: string(value, "stage")
+			.and_then(|s| value_of(STAGES, &s))
+			.unwrap_or(Stage::None),
+		parameters: objects(value, "parameters")?
+			.map(var_layout_from_json)
+			.collect::<Result<_, _>>()?,
+		result: result.map(var_layout_from_json).transpose()?,
+		compute_thread_group_size: thread_group_size,
+		compute_wave_size: 0,
+		uses_any_sample_rate_input: value
+			.get("usesAnySampleRateInput")
+			.and_then(Value::as_bool)
+			.unwrap_or(false),
+		has_default_constant_buffer: false,
+		user_attributes: user_attributes_from_json(value)?,
+	})
+}
+
+fn var_layout_from_json(value: &Value) -> Result<VariableLayout, ReflectionError> {
+	let mut type_layout = match value.get("type") {
+		Some(ty) => type_layout_from_json(ty)?,
+		None => TypeLayout::empty(),
+	};
+
+	let bindings = match (value.get("binding"), value.get("bindings")) {
+		(Some(binding), _) => std::slice::from_ref(binding),
+		(None, Some(Value::Array(bindings))) => bindings.as_slice(),
+		(None, Some(_)) => return Err(error("'bindings' must be an array")),
+		(None, None) => &[],
+	};
+
+	let mut offsets = Vec::with_capacity(bindings.len());
+	for binding in bindings {
+		let (offset, size) = binding_from_json(binding)?;
+		if type_layout.size(offset.category) == 0 && size != 0 {
+			type_layout.sizes.push(Size {
+				category: offset.category,
+				size,
+				stride: 0,
+				alignment: 0,
+			});
+		}
+		offsets.push(offset);
+	}
+
+	Ok(VariableLayout {
+		name: string(value, "name"),
+		type_layout,
+		offsets,
+		semantic_name: string(value, "semanticName"),
+		semantic_index: number(value, "semanticIndex").unwrap_or(0) as usize,
+		image_format: snapshot::unknown_image_format(),
+		stage: string(value, "stage")
+			.and_then(|s| value_of(STAGES, &s))
+			.unwrap_or(Stage::None),
+		user_attributes: user_attributes_from_json(value)?,
+	})
+}
+
+/// Returns the offset described by a binding object together with the size it covers.
+fn binding_from_json(value: &Value) -> Result<(Offset, usize), ReflectionError> {
+	let kind = string(value, "kind").ok_or_else(|| error("binding without 'kind'"))?;
+	let category = value_of(CATEGORIES, &kind)
+		.ok_or_else(|| error(format!("unknown binding kind '{kind}'")))?;
+
+	if category == ParameterCategory::Uniform {
+		let offset = Offset {
+			category,
+			offset: number(value, "offset").unwrap_or(0) as usize,
+			space: 0,
+		};
+		return Ok((offset, number(value, "size").unwrap_or(0) as usize));
+	}
+
+	let count = match value.get("count") {
+		None => 1,
+		Some(Value::String(s)) if s == "unbounded" => UNBOUNDED_SIZE,
+		Some(count) => count
+			.as_u64()
+			.ok_or_else(|| error("invalid binding 'count'"))? as usize,
+	};
+	let offset = Offset {
+		category,
+		offset: number(value, "index").unwrap_or(0) as usize,
+		space: number(value, "space").unwrap_or(0) as usize,
+	};
+	Ok((offset, count))
+}
+
+fn type_layout_from_json(value: &Value) -> Result<TypeLayout, ReflectionError> {
+	let ty = type_from_json(value)?;
+	let mut type_layout = TypeLayout::empty();
+	type_layout.kind = ty.kind;
+	type_layout.matrix_layout_mode = MatrixLayoutMode::ModeUnknown;
+
+	type_layout.fields = objects(value, "fields")?
+		.map(var_layout_from_json)
+		.collect::<Result<_, _>>()?;
+
+	if let Some(element_var_layout) = value.get("elementVarLayout") {
+		type_layout.element_var_layout = Some(Box::new(var_layout_from_json(element_var_layout)?));
+	} else if let Some(element) = value.get("elementType").or_else(|| value.get("resultType")) {
+		type_layout.element_type_layout = Some(Box::new(type_layout_from_json(element)?));
+	}
+	if let Some(container) = value.get("containerVarLayout") {
+		type_layout.container_var_layout = Some(Box::new(var_layout_from_json(container)?));
+	}
+
+	if let (Some(stride), Some(element)) = (
+		number(value, "uniformStride"),
+		type_layout.element_type_layout.as_mut(),
+	) {
+		match element
+			.sizes
+			.iter_mut()
+			.find(|s| s.category == ParameterCategory::Uniform)
+		{
+			Some(size) => size.stride = stride as usize,
+			None => element.sizes.push(Size {
+				category: ParameterCategory::Uniform,
+				size: 0,
+				stride: stride as usize,
+				alignment: 0,
+			}),
+		}
+	}
+
+	type_layout.ty = Some(ty);
+	Ok(type_layout)
+}
+
+fn type_from_json(value: &Value) -> Result<Type, ReflectionError> {
+	let kind_name = string(value, "kind").ok_or_else(|| error("type without 'kind'"))?;
+	let kind = match kind_name.as_str() {
+		"struct" => TypeKind::Struct,
+		"array" => TypeKind::Array,
+		"matrix" => TypeKind::Matrix,
+		"vector" => TypeKind::Vector,
+		"scalar" => TypeKind::Scalar,
+		"resource" => TypeKind::Resource,
+		"samplerState" => TypeKind::SamplerState,
+		"pointer" => TypeKind::Pointer,
+		"GenericTypeParameter" => TypeKind::GenericTypeParameter,
+		"Interface" => TypeKind::Interface,
+		"feedback" => TypeKind::Feedback,
+		"DynamicResource" => TypeKind::DynamicResource,
+		name => value_of(PARAMETER_GROUP_KINDS, name).unwrap_or(TypeKind::None),
+	};
+
+	let mut shape_flags = 0;
+	for (flag, name) in [
+		(RESOURCE_SHAPE_ARRAY_FLAG, "array"),
+		(RESOURCE_SHAPE_MULTISAMPLE_FLAG, "multisample"),
+		(RESOURCE_SHAPE_FEEDBACK_FLAG, "feedback"),
+	] {
+		if value.get(name).and_then(Value::as_bool) == Some(true) {
+			shape_flags |= flag;
+		}
+	}
+	let base_shape = string(value, "baseShape")
+		.and_then(|s| value_of(BASE_SHAPES, &s))
+		.unwrap_or(0);
+
+	let element_type = match value.get("elementType") {
+		Some(element) => Some(Box::new(type_from_json(element)?)),
+		None => None,
+	};
+	let resource_result_type = match value.get("resultType") {
+		Some(result) if kind == TypeKind::Resource => Some(Box::new(type_from_json(result)?)),
+		_ => None,
+	};
+
+	Ok(Type {
+		kind,
+		name: string(value, "name"),
+		full_name: None,
+		element_count: number(value, "elementCount").unwrap_or(0) as usize,
+		element_type,
+		row_count: number(value, "rowCount").unwrap_or(0) as u32,
+		column_count: number(value, "columnCount").unwrap_or(0) as u32,
+		scalar_type: string(value, "scalarType")
+			.and_then(|s| value_of(SCALAR_TYPES, &s))
+			.unwrap_or(ScalarType::None),
+		resource_shape: snapshot::base_resource_shape(base_shape),
+		resource_shape_flags: shape_flags,
+		resource_access: match string(value, "access") {
+			Some(access) => value_of(ACCESSES, &access).unwrap_or(ResourceAccess::None),
+			None if kind == TypeKind::Resource => ResourceAccess::Read,
+			None => ResourceAccess::None,
+		},
+		resource_result_type,
+		user_attributes: user_attributes_from_json(value)?,
+	})
+}
+
+fn user_attributes_from_json(value: &Value) -> Result<Vec<UserAttribute>, ReflectionError> {
+	objects(value, "userAttribs")?
+		.map(|attribute| {
+			Ok(UserAttribute {
+				name: string(attribute, "name").ok_or_else(|| error("attribute without 'name'"))?,
+				arguments: objects(attribute, "arguments")?
+					.map(|argument| match argument {
+						Value::Number(n) => match n.as_i64() {
+							Some(i) => AttributeArgument::Int(i as i32),
+							None => AttributeArgument::Float(n.as_f64().unwrap_or(0.0) as f32),
+						},
+						Value::String(s) => AttributeArgument::String(s.clone()),
+						_ => AttributeArgument::Unknown,
+					})
+					.collect(),
+			})
+		})
+		.collect()
+}
@@ -1,30 +1,52 @@
+pub mod bindings;
+pub mod codegen;
+mod cursor;
 mod decl;
 mod entry_point;
 mod error;
 mod function;
 mod generic;
 pub mod helpers;
+mod host_layout;
+#[cfg(feature = "json")]
+mod json;
+mod render_target;
 mod shader;
+pub mod snapshot;
+mod specialization;
+mod stage_interface;
 mod ty;
 mod type_layout;
 mod type_parameter;
 mod user_attribute;
 mod variable;
 mod variable_layout;
+mod vertex_input;
 
+pub use cursor::{ShaderCursor, ShaderScalar};
 pub use decl::Decl;
 pub use entry_point::EntryPoint;
 pub use error::{AttributeError, ReflectionError};
 pub use function::Function;
 pub use generic::Generic;
 pub use helpers::SlangAttribute;
+pub use host_layout::{
+	HostField, HostType, LayoutMismatch, LayoutMismatchKind, ShaderLayout, check_layout,
+	check_variable_layout,
+};
+pub use render_target::{DepthOutput, RenderTarget, RenderTargets};
 pub use shader::Shader;
+pub use specialization::{SpecializationParam, SpecializationParamKind};
+pub use stage_interface::{
+	StageInterfaceMismatch, StageInterfaceMismatchKind, check_stage_interface,
+};
 pub use ty::Type;
 pub use type_layout::TypeLayout;
 pub use type_parameter::TypeParameter;
 pub use user_attribute::UserAttribute;
 pub use variable::Variable;
 pub use variable_layout::VariableLayout;
+pub use vertex_input::{VertexAttribute, VertexFormat};
 
 use super::sys;
 use std::ffi::CString;
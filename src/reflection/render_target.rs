@@ -0,0 +1,94 @@
+use super::{EntryPoint, VariableLayout, stage_interface};
+use crate::{ParameterCategory, ScalarType, Stage, TypeKind};
+
+/// A color output of a fragment entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderTarget {
+	/// Dotted path to the output, e.g. `return.albedo`.
+	pub name: String,
+	/// The output location, which is also the `SV_Target` index.
+	pub index: u32,
+	pub scalar_type: ScalarType,
+	pub component_count: u32,
+}
+
+/// How a fragment entry point writes depth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DepthOutput {
+	/// `SV_Depth`
+	Any,
+	/// `SV_DepthGreaterEqual`
+	GreaterEqual,
+	/// `SV_DepthLessEqual`
+	LessEqual,
+}
+
+impl DepthOutput {
+	fn from_semantic(semantic: &str) -> Option<Self> {
+		[
+			("SV_Depth", Self::Any),
+			("SV_DepthGreaterEqual", Self::GreaterEqual),
+			("SV_DepthLessEqual", Self::LessEqual),
+		]
+		.into_iter()
+		.find_map(|(name, depth)| semantic.eq_ignore_ascii_case(name).then_some(depth))
+	}
+}
+
+/// The outputs of a fragment entry point.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RenderTargets {
+	/// Color outputs, sorted by index. Indices can have gaps.
+	pub color: Vec<RenderTarget>,
+	pub depth: Option<DepthOutput>,
+}
+
+impl RenderTargets {
+	/// Number of color attachment slots needed, i.e. one past the highest index.
+	pub fn slot_count(&self) -> u32 {
+		self.color.last().map_or(0, |target| target.index + 1)
+	}
+
+	pub fn writes_depth(&self) -> bool {
+		self.depth.is_some()
+	}
+}
+
+pub(super) fn collect(entry_point: &EntryPoint) -> RenderTargets {
+	if entry_point.stage() != Stage::Fragment {
+		return RenderTargets::default();
+	}
+
+	let mut color = stage_interface::varyings(entry_point, ParameterCategory::VaryingOutput)
+		.into_iter()
+		.map(|output| RenderTarget {
+			name: output.name,
+			index: output.location,
+			scalar_type: output.scalar_type,
+			component_count: output.component_count,
+		})
+		.collect::<Vec<_>>();
+	color.sort_by_key(|target| target.index);
+
+	// Depth is a system value without a location, so it is found by semantic.
+	let depth = entry_point
+		.parameters()
+		.chain(entry_point.result_var_layout())
+		.find_map(find_depth);
+
+	RenderTargets { color, depth }
+}
+
+fn find_depth(var: &VariableLayout) -> Option<DepthOutput> {
+	if let Some(depth) = var.semantic_name().and_then(DepthOutput::from_semantic) {
+		return Some(depth);
+	}
+	let layout = var.type_layout()?;
+	if layout.kind() != TypeKind::Struct {
+		return None;
+	}
+	layout.fields().find_map(find_depth)
+}
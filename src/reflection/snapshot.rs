@@ -0,0 +1,538 @@
+//! Owned copies of the reflection data.
+//!
+//! The types in [`reflection`](crate::reflection) are borrowed views into data owned by a Slang session. The types in
+//! this module mirror them as plain Rust values that can outlive the session and, with the `serde` feature enabled, be
+//! serialized to disk and loaded again without linking against Slang.
+
+use crate::reflection;
+use crate::{
+	BindingType, ImageFormat, MatrixLayoutMode, ParameterCategory, ResourceAccess, ResourceShape,
+	ScalarType, Stage, TypeKind,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Shader {
+	pub parameters: Vec<VariableLayout>,
+	pub entry_points: Vec<EntryPoint>,
+	pub global_constant_buffer_binding: u64,
+	pub global_constant_buffer_size: usize,
+	pub hashed_strings: Vec<HashedString>,
+}
+
+impl Shader {
+	pub fn find_parameter_by_name(&self, name: &str) -> Option<&VariableLayout> {
+		self.parameters
+			.iter()
+			.find(|p| p.name.as_deref() == Some(name))
+	}
+
+	pub fn find_entry_point_by_name(&self, name: &str) -> Option<&EntryPoint> {
+		self.entry_points
+			.iter()
+			.find(|e| e.name.as_deref() == Some(name))
+	}
+}
+
+/// A string passed to `getStringHash` in the shader, with the hash Slang computes for it.
+///
+/// The hash is stored so that snapshots can be written without Slang.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HashedString {
+	pub string: String,
+	pub hash: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntryPoint {
+	pub name: Option<String>,
+	pub name_override: Option<String>,
+	pub stage: Stage,
+	pub parameters: Vec<VariableLayout>,
+	pub result: Option<VariableLayout>,
+	pub compute_thread_group_size: [u64; 3],
+	pub compute_wave_size: u64,
+	pub uses_any_sample_rate_input: bool,
+	pub has_default_constant_buffer: bool,
+	/// The user attributes attached to the entry point function.
+	pub user_attributes: Vec<UserAttribute>,
+}
+
+/// The location of a [`VariableLayout`] for one of the resource kinds it consumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Offset {
+	pub category: ParameterCategory,
+	pub offset: usize,
+	pub space: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VariableLayout {
+	pub name: Option<String>,
+	pub type_layout: TypeLayout,
+	pub offsets: Vec<Offset>,
+	pub semantic_name: Option<String>,
+	pub semantic_index: usize,
+	pub image_format: ImageFormat,
+	pub stage: Stage,
+	pub user_attributes: Vec<UserAttribute>,
+}
+
+impl VariableLayout {
+	pub fn category(&self) -> ParameterCategory {
+		self.type_layout.parameter_category
+	}
+
+	pub fn categories(&self) -> impl ExactSizeIterator<Item = ParameterCategory> {
+		self.offsets.iter().map(|o| o.category)
+	}
+
+	/// Same as [`reflection::VariableLayout::offset`], i.e. `0` for categories the variable does not consume.
+	pub fn offset(&self, category: ParameterCategory) -> usize {
+		self.offsets
+			.iter()
+			.find(|o| o.category == category)
+			.map_or(0, |o| o.offset)
+	}
+
+	pub fn binding_space_with_category(&self, category: ParameterCategory) -> usize {
+		self.offsets
+			.iter()
+			.find(|o| o.category == category)
+			.map_or(0, |o| o.space)
+	}
+
+	pub fn find_user_attribute_by_name(&self, name: &str) -> Option<&UserAttribute> {
+		self.user_attributes.iter().find(|a| a.name == name)
+	}
+}
+
+/// The size of a [`TypeLayout`] for one of the resource kinds it consumes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size {
+	pub category: ParameterCategory,
+	pub size: usize,
+	pub stride: usize,
+	pub alignment: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TypeLayout {
+	pub ty: Option<Type>,
+	pub kind: TypeKind,
+	pub parameter_category: ParameterCategory,
+	pub sizes: Vec<Size>,
+	pub matrix_layout_mode: MatrixLayoutMode,
+	pub generic_param_index: i32,
+	pub fields: Vec<VariableLayout>,
+	/// The layout of the elements of arrays, structured buffers and the like. Left empty for types that have an
+	/// [`element_var_layout`](Self::element_var_layout), whose type layout is the same.
+	pub element_type_layout: Option<Box<TypeLayout>>,
+	/// The layout of the element of parameter groups, e.g. the struct inside a `ConstantBuffer`.
+	pub element_var_layout: Option<Box<VariableLayout>>,
+	/// The layout of the container of parameter groups, e.g. the buffer binding of a `ConstantBuffer`.
+	pub container_var_layout: Option<Box<VariableLayout>>,
+	pub explicit_counter: Option<Box<VariableLayout>>,
+	pub binding_ranges: Vec<BindingRange>,
+	pub descriptor_sets: Vec<DescriptorSet>,
+	pub sub_object_ranges: Vec<SubObjectRange>,
+}
+
+impl TypeLayout {
+	pub fn name(&self) -> Option<&str> {
+		self.ty.as_ref()?.name.as_deref()
+	}
+
+	/// Same as [`reflection::TypeLayout::size`], i.e. `0` for categories the type does not consume.
+	pub fn size(&self, category: ParameterCategory) -> usize {
+		self.size_entry(category).map_or(0, |s| s.size)
+	}
+
+	pub fn stride(&self, category: ParameterCategory) -> usize {
+		self.size_entry(category).map_or(0, |s| s.stride)
+	}
+
+	pub fn alignment(&self, category: ParameterCategory) -> i32 {
+		self.size_entry(category).map_or(0, |s| s.alignment)
+	}
+
+	pub fn categories(&self) -> impl ExactSizeIterator<Item = ParameterCategory> {
+		self.sizes.iter().map(|s| s.category)
+	}
+
+	/// The element layout, regardless of whether it was recorded as a type or a variable layout.
+	pub fn element(&self) -> Option<&TypeLayout> {
+		self.element_type_layout
+			.as_deref()
+			.or_else(|| Some(&self.element_var_layout.as_ref()?.type_layout))
+	}
+
+	pub fn find_field_by_name(&self, name: &str) -> Option<&VariableLayout> {
+		self.fields.iter().find(|f| f.name.as_deref() == Some(name))
+	}
+
+	fn size_entry(&self, category: ParameterCategory) -> Option<&Size> {
+		self.sizes.iter().find(|s| s.category == category)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BindingRange {
+	pub binding_type: BindingType,
+	pub binding_count: i64,
+	pub is_specializable: bool,
+	pub image_format: ImageFormat,
+	pub descriptor_set_index: i64,
+	pub first_descriptor_range_index: i64,
+	pub descriptor_range_count: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorSet {
+	pub space_offset: i64,
+	pub descriptor_ranges: Vec<DescriptorRange>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DescriptorRange {
+	pub index_offset: i64,
+	pub descriptor_count: i64,
+	pub binding_type: BindingType,
+	pub category: ParameterCategory,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubObjectRange {
+	pub binding_range_index: i64,
+	pub space_offset: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Type {
+	pub kind: TypeKind,
+	pub name: Option<String>,
+	pub full_name: Option<String>,
+	pub element_count: usize,
+	/// The element type of arrays, vectors, matrices and parameter groups.
+	pub element_type: Option<Box<Type>>,
+	pub row_count: u32,
+	pub column_count: u32,
+	pub scalar_type: ScalarType,
+	/// The base shape of resource types, without any of the flags Slang may combine it with.
+	pub resource_shape: ResourceShape,
+	/// The `SLANG_TEXTURE_*_FLAG` bits of the resource shape, e.g. [`RESOURCE_SHAPE_ARRAY_FLAG`].
+	pub resource_shape_flags: u32,
+	pub resource_access: ResourceAccess,
+	pub resource_result_type: Option<Box<Type>>,
+	pub user_attributes: Vec<UserAttribute>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserAttribute {
+	pub name: String,
+	pub arguments: Vec<AttributeArgument>,
+}
+
+/// A literal argument of a [`UserAttribute`]. Slang only exposes integer, float and string literals.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttributeArgument {
+	Int(i32),
+	Float(f32),
+	String(String),
+	/// The value could not be retrieved through the reflection API.
+	Unknown,
+}
+
+pub const RESOURCE_BASE_SHAPE_MASK: u32 = 0x0f;
+pub const RESOURCE_SHAPE_FEEDBACK_FLAG: u32 = 0x10;
+pub const RESOURCE_SHAPE_SHADOW_FLAG: u32 = 0x20;
+pub const RESOURCE_SHAPE_ARRAY_FLAG: u32 = 0x40;
+pub const RESOURCE_SHAPE_MULTISAMPLE_FLAG: u32 = 0x80;
+pub const RESOURCE_SHAPE_COMBINED_FLAG: u32 = 0x100;
+
+/// Slang combines resource shapes with flag bits, which yields values that are not valid [`ResourceShape`]s. Only the
+/// base shape is stored as an enum.
+pub(crate) fn base_resource_shape(shape: u32) -> ResourceShape {
+	// The base shapes are numbered consecutively up to `SLANG_TEXTURE_SUBPASS`.
+	const RESOURCE_UNKNOWN: u32 = 8;
+	const TEXTURE_SUBPASS: u32 = 10;
+
+	let base = match shape & RESOURCE_BASE_SHAPE_MASK {
+		base if base <= TEXTURE_SUBPASS => base,
+		_ => RESOURCE_UNKNOWN,
+	};
+	// SAFETY: All values up to TEXTURE_SUBPASS are variants of the `#[repr(u32)]` enum.
+	unsafe { std::mem::transmute::<u32, ResourceShape>(base) }
+}
+
+/// `SLANG_IMAGE_FORMAT_unknown`, for data that does not specify an image format.
+#[cfg(feature = "json")]
+pub(crate) fn unknown_image_format() -> ImageFormat {
+	// SAFETY: The image formats are a `#[repr(u32)]` enum and `unknown` is its first variant.
+	unsafe { std::mem::transmute::<u32, ImageFormat>(0) }
+}
+
+impl From<&reflection::Shader> for Shader {
+	fn from(shader: &reflection::Shader) -> Self {
+		Self {
+			parameters: shader.parameters().map(Into::into).collect(),
+			entry_points: shader.entry_points().map(Into::into).collect(),
+			global_constant_buffer_binding: shader.global_constant_buffer_binding(),
+			global_constant_buffer_size: shader.global_constant_buffer_size(),
+			hashed_strings: shader
+				.hashed_strings()
+				.map(|string| HashedString {
+					string: string.to_string(),
+					hash: reflection::compute_string_hash(string),
+				})
+				.collect(),
+		}
+	}
+}
+
+impl From<&reflection::EntryPoint> for EntryPoint {
+	fn from(entry_point: &reflection::EntryPoint) -> Self {
+		Self {
+			name: entry_point.name().map(str::to_string),
+			name_override: entry_point.name_override().map(str::to_string),
+			stage: entry_point.stage(),
+			parameters: entry_point.parameters().map(Into::into).collect(),
+			result: entry_point.result_var_layout().map(Into::into),
+			compute_thread_group_size: entry_point.compute_thread_group_size(),
+			compute_wave_size: entry_point.compute_wave_size(),
+			uses_any_sample_rate_input: entry_point.uses_any_sample_rate_input(),
+			has_default_constant_buffer: entry_point.has_default_constant_buffer(),
+			user_attributes: entry_point
+				.function()
+				.map(|f| f.user_attributes().map(Into::into).collect())
+				.unwrap_or_default(),
+		}
+	}
+}
+
+impl From<&reflection::VariableLayout> for VariableLayout {
+	fn from(var_layout: &reflection::VariableLayout) -> Self {
+		let type_layout = var_layout
+			.type_layout()
+			.map(TypeLayout::from)
+			.unwrap_or_else(TypeLayout::empty);
+
+		Self {
+			name: var_layout.name().map(str::to_string),
+			offsets: var_layout
+				.categories()
+				.map(|category| Offset {
+					category,
+					offset: var_layout.offset(category),
+					space: var_layout.binding_space_with_category(category),
+				})
+				.collect(),
+			type_layout,
+			semantic_name: var_layout.semantic_name().map(str::to_string),
+			semantic_index: var_layout.semantic_index(),
+			image_format: var_layout.image_format(),
+			stage: var_layout.stage(),
+			user_attributes: var_layout
+				.variable()
+				.map(|v| v.user_attributes().map(Into::into).collect())
+				.unwrap_or_default(),
+		}
+	}
+}
+
+impl TypeLayout {
+	/// Stands in for a type layout Slang did not report.
+	pub(crate) fn empty() -> Self {
+		Self {
+			ty: None,
+			kind: TypeKind::None,
+			parameter_category: ParameterCategory::None,
+			sizes: Vec::new(),
+			matrix_layout_mode: MatrixLayoutMode::ModeUnknown,
+			generic_param_index: -1,
+			fields: Vec::new(),
+			element_type_layout: None,
+			element_var_layout: None,
+			container_var_layout: None,
+			explicit_counter: None,
+			binding_ranges: Vec::new(),
+			descriptor_sets: Vec::new(),
+			sub_object_ranges: Vec::new(),
+		}
+	}
+}
+
+impl From<&reflection::TypeLayout> for TypeLayout {
+	fn from(type_layout: &reflection::TypeLayout) -> Self {
+		let kind = type_layout.kind();
+		// Pointers may refer back to the type containing them, so their target is not captured.
+		let is_pointer = kind == TypeKind::Pointer;
+
+		let element_var_layout = type_layout
+			.element_var_layout()
+			.filter(|_| !is_pointer)
+			.map(|v| Box::new(v.into()));
+		let element_type_layout = type_layout
+			.element_type_layout()
+			.filter(|_| !is_pointer && element_var_layout.is_none())
+			.map(|t| Box::new(t.into()));
+
+		Self {
+			ty: type_layout.ty().map(Into::into),
+			kind,
+			parameter_category: type_layout.parameter_category(),
+			sizes: type_layout
+				.categories()
+				.map(|category| Size {
+					category,
+					size: type_layout.size(category),
+					stride: type_layout.stride(category),
+					alignment: type_layout.alignment(category),
+				})
+				.collect(),
+			matrix_layout_mode: type_layout.matrix_layout_mode(),
+			generic_param_index: type_layout.generic_param_index(),
+			fields: type_layout.fields().map(Into::into).collect(),
+			element_type_layout,
+			element_var_layout,
+			container_var_layout: type_layout
+				.container_var_layout()
+				.map(|v| Box::new(v.into())),
+			explicit_counter: type_layout.explicit_counter().map(|v| Box::new(v.into())),
+			binding_ranges: (0..type_layout.binding_range_count())
+				.map(|i| BindingRange {
+					binding_type: type_layout.binding_range_type(i),
+					binding_count: type_layout.binding_range_binding_count(i),
+					is_specializable: type_layout.is_binding_range_specializable(i),
+					image_format: type_layout.binding_range_image_format(i),
+					descriptor_set_index: type_layout.binding_range_descriptor_set_index(i),
+					first_descriptor_range_index: type_layout
+						.binding_range_first_descriptor_range_index(i),
+					descriptor_range_count: type_layout.binding_range_descriptor_range_count(i),
+				})
+				.collect(),
+			descriptor_sets: (0..type_layout.descriptor_set_count())
+				.map(|set| DescriptorSet {
+					space_offset: type_layout.descriptor_set_space_offset(set),
+					descriptor_ranges: (0..type_layout.descriptor_set_descriptor_range_count(set))
+						.map(|range| DescriptorRange {
+							index_offset: type_layout
+								.descriptor_set_descriptor_range_index_offset(set, range),
+							descriptor_count: type_layout
+								.descriptor_set_descriptor_range_descriptor_count(set, range),
+							binding_type: type_layout
+								.descriptor_set_descriptor_range_type(set, range),
+							category: type_layout
+								.descriptor_set_descriptor_range_category(set, range),
+						})
+						.collect(),
+				})
+				.collect(),
+			sub_object_ranges: (0..type_layout.sub_object_range_count())
+				.map(|i| SubObjectRange {
+					binding_range_index: type_layout.sub_object_range_binding_range_index(i),
+					space_offset: type_layout.sub_object_range_space_offset(i),
+				})
+				.collect(),
+		}
+	}
+}
+
+impl From<&reflection::Type> for Type {
+	fn from(ty: &reflection::Type) -> Self {
+		let kind = ty.kind();
+		let is_pointer = kind == TypeKind::Pointer;
+
+		Self {
+			kind,
+			name: ty.name().map(str::to_string),
+			full_name: ty
+				.full_name()
+				.ok()
+				.and_then(|name| name.as_str().ok().map(str::to_string)),
+			element_count: ty.element_count(),
+			element_type: ty
+				.element_type()
+				.filter(|_| !is_pointer)
+				.map(|t| Box::new(t.into())),
+			row_count: ty.row_count(),
+			column_count: ty.column_count(),
+			scalar_type: ty.scalar_type(),
+			resource_shape: base_resource_shape(ty.resource_shape() as u32),
+			resource_shape_flags: ty.resource_shape() as u32 & !RESOURCE_BASE_SHAPE_MASK,
+			resource_access: ty.resource_access(),
+			resource_result_type: (kind == TypeKind::Resource)
+				.then(|| ty.resource_result_type())
+				.flatten()
+				.map(|t| Box::new(t.into())),
+			user_attributes: ty.user_attributes().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<&reflection::UserAttribute> for UserAttribute {
+	fn from(attribute: &reflection::UserAttribute) -> Self {
+		Self {
+			name: attribute.name().unwrap_or_default().to_string(),
+			arguments: (0..attribute.argument_count())
+				.map(|i| argument(attribute, i))
+				.collect(),
+		}
+	}
+}
+
+fn argument(attribute: &reflection::UserAttribute, index: u32) -> AttributeArgument {
+	let scalar_type = attribute
+		.argument_type(index)
+		.filter(|ty| ty.kind() == TypeKind::Scalar)
+		.map(|ty| ty.scalar_type());
+
+	let value = match scalar_type {
+		Some(ScalarType::Float16 | ScalarType::Float32 | ScalarType::Float64) => attribute
+			.argument_value_float(index)
+			.map(AttributeArgument::Float),
+		Some(_) => attribute
+			.argument_value_int(index)
+			.map(AttributeArgument::Int),
+		None => attribute
+			.argument_value_string(index)
+			.map(|s| AttributeArgument::String(s.to_string())),
+	};
+
+	value.unwrap_or(AttributeArgument::Unknown)
+}
+
+macro_rules! snapshot_method {
+	($($ty:ident),*) => {
+		$(
+			impl reflection::$ty {
+				/// Copies this reflection object into an owned [`snapshot`](crate::reflection::snapshot) value.
+				pub fn snapshot(&self) -> $ty {
+					self.into()
+				}
+			}
+		)*
+	};
+}
+
+snapshot_method!(
+	Shader,
+	EntryPoint,
+	VariableLayout,
+	TypeLayout,
+	Type,
+	UserAttribute
+);
@@ -0,0 +1,125 @@
+use super::{Generic, Shader, Type, Variable, rcall};
+use crate::{DeclKind, TypeKind, sys};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpecializationParamKind {
+	/// A generic type parameter, declared with `type_param` or on a generic entry point.
+	Generic,
+	/// A parameter of interface type, or a field or element of one, which is specialized to a concrete type.
+	Existential,
+}
+
+/// What an argument to [`ComponentType::specialize`](crate::ComponentType::specialize) must provide.
+pub struct SpecializationParam<'a> {
+	pub kind: SpecializationParamKind,
+	/// The name of the type parameter, or of the shader parameter or field with the interface type.
+	pub name: Option<&'a str>,
+	/// The entry point declaring the parameter, or `None` for global parameters.
+	pub entry_point: Option<&'a str>,
+	/// Interfaces the type argument must conform to.
+	pub constraints: Vec<&'a Type>,
+}
+
+impl Shader {
+	/// Lists the specialization parameters of the program, in the order Slang expects the arguments: global generic
+	/// parameters, global interface-typed parameters, then the generic and interface-typed parameters of each entry
+	/// point.
+	///
+	/// The program must be unspecialized, as this is derived from the declared types of the parameters.
+	pub fn specialization_params(&self) -> Vec<SpecializationParam<'_>> {
+		let mut params = Vec::new();
+
+		for type_param in self.type_parameters() {
+			params.push(SpecializationParam {
+				kind: SpecializationParamKind::Generic,
+				name: type_param.name(),
+				entry_point: None,
+				constraints: type_param.constraints().collect(),
+			});
+		}
+		for parameter in self.parameters() {
+			if let Some(variable) = parameter.variable() {
+				existential_params(variable, None, &mut params);
+			}
+		}
+
+		for entry_point in self.entry_points() {
+			let name = entry_point.name();
+			if let Some(generic) = entry_point
+				.function()
+				.and_then(|f| f.generic_container())
+				.filter(|g| g.inner_kind() == DeclKind::Func)
+			{
+				generic_params(generic, name, &mut params);
+			}
+			for parameter in entry_point.parameters() {
+				if let Some(variable) = parameter.variable() {
+					existential_params(variable, name, &mut params);
+				}
+			}
+		}
+
+		params
+	}
+}
+
+fn generic_params<'a>(
+	generic: &'a Generic,
+	entry_point: Option<&'a str>,
+	params: &mut Vec<SpecializationParam<'a>>,
+) {
+	for index in 0..generic.type_parameter_count() {
+		// Type parameters of a generic are variables, unlike the `type_param` declarations of a program.
+		let Some(variable) =
+			rcall!(spReflectionGeneric_GetTypeParameter(generic, index) as Option<&Variable>)
+		else {
+			continue;
+		};
+		params.push(SpecializationParam {
+			kind: SpecializationParamKind::Generic,
+			name: variable.name(),
+			entry_point,
+			constraints: (0..generic.type_parameter_constraint_count(variable))
+				.filter_map(|i| generic.type_parameter_constraint_by_index(variable, i))
+				.collect(),
+		});
+	}
+}
+
+fn existential_params<'a>(
+	variable: &'a Variable,
+	entry_point: Option<&'a str>,
+	params: &mut Vec<SpecializationParam<'a>>,
+) {
+	let Some(ty) = variable.ty() else {
+		return;
+	};
+
+	let mut ty = ty;
+	while let TypeKind::Array
+	| TypeKind::ConstantBuffer
+	| TypeKind::ParameterBlock
+	| TypeKind::TextureBuffer
+	| TypeKind::ShaderStorageBuffer = ty.kind()
+	{
+		match ty.element_type() {
+			Some(element) => ty = element,
+			None => return,
+		}
+	}
+
+	match ty.kind() {
+		TypeKind::Interface => params.push(SpecializationParam {
+			kind: SpecializationParamKind::Existential,
+			name: variable.name(),
+			entry_point,
+			constraints: vec![ty],
+		}),
+		TypeKind::Struct => {
+			for field in ty.fields() {
+				existential_params(field, entry_point, params);
+			}
+		}
+		_ => {}
+	}
+}
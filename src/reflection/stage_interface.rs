@@ -0,0 +1,141 @@
+use super::vertex_input::{Semantic, Varyings};
+use super::{EntryPoint, VariableLayout, VertexAttribute};
+use crate::{ParameterCategory, ScalarType, Stage, TypeKind};
+use thiserror::Error;
+
+/// A difference between the outputs of one stage and the inputs of the next.
+#[derive(Debug, Error, Clone, PartialEq)]
+#[error("input `{name}` at location {location}: {kind}")]
+pub struct StageInterfaceMismatch {
+	/// Dotted path to the consumer input, e.g. `input.skin.weights`.
+	pub name: String,
+	pub location: u32,
+	pub kind: StageInterfaceMismatchKind,
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum StageInterfaceMismatchKind {
+	/// No output of the producer is written to the location.
+	#[error("not written by the previous stage")]
+	MissingOutput,
+
+	#[error("output semantic {output}, input semantic {input}")]
+	Semantic { output: String, input: String },
+
+	#[error("output scalar type {output:?}, input scalar type {input:?}")]
+	ScalarType {
+		output: ScalarType,
+		input: ScalarType,
+	},
+
+	/// The input reads more components than the output writes.
+	#[error("output has {output} components, input has {input}")]
+	ComponentCount { output: u32, input: u32 },
+}
+
+/// Compares the varying outputs of `producer` against the varying inputs of `consumer` and returns every mismatch.
+///
+/// Varyings are flattened to one entry per location and matched by location. Semantics are compared
+/// case-insensitively when both sides declare one, and outputs that the consumer does not read are allowed. For
+/// stages that read or write per-vertex arrays (hull, domain and geometry inputs, hull outputs), the array dimension is
+/// stripped before comparing. Mesh `OutputVertices`/`OutputPrimitives` and geometry stream outputs are compared by
+/// their element type.
+///
+/// Interpolation modifiers such as `nointerpolation` are not exposed by the reflection API, so they cannot be checked
+/// here.
+pub fn check_stage_interface(
+	producer: &EntryPoint,
+	consumer: &EntryPoint,
+) -> Vec<StageInterfaceMismatch> {
+	let outputs = varyings(producer, ParameterCategory::VaryingOutput);
+	let inputs = varyings(consumer, ParameterCategory::VaryingInput);
+
+	let mut mismatches = Vec::new();
+	for input in &inputs {
+		let mut push = |kind| {
+			mismatches.push(StageInterfaceMismatch {
+				name: input.name.clone(),
+				location: input.location,
+				kind,
+			})
+		};
+
+		let Some(output) = outputs.iter().find(|o| o.location == input.location) else {
+			push(StageInterfaceMismatchKind::MissingOutput);
+			continue;
+		};
+
+		if let (Some(output_name), Some(input_name)) = (&output.semantic_name, &input.semantic_name)
+			&& (!output_name.eq_ignore_ascii_case(input_name)
+				|| output.semantic_index != input.semantic_index)
+		{
+			push(StageInterfaceMismatchKind::Semantic {
+				output: format!("{output_name}{}", output.semantic_index),
+				input: format!("{input_name}{}", input.semantic_index),
+			});
+		}
+
+		if output.scalar_type != input.scalar_type {
+			push(StageInterfaceMismatchKind::ScalarType {
+				output: output.scalar_type,
+				input: input.scalar_type,
+			});
+		}
+
+		if output.component_count < input.component_count {
+			push(StageInterfaceMismatchKind::ComponentCount {
+				output: output.component_count,
+				input: input.component_count,
+			});
+		}
+	}
+	mismatches
+}
+
+pub(super) fn varyings(
+	entry_point: &EntryPoint,
+	category: ParameterCategory,
+) -> Vec<VertexAttribute> {
+	let per_vertex = match category {
+		ParameterCategory::VaryingInput => matches!(
+			entry_point.stage(),
+			Stage::Hull | Stage::Domain | Stage::Geometry
+		),
+		_ => entry_point.stage() == Stage::Hull,
+	};
+
+	let mut varyings = Varyings::new(category);
+	let mut add = |var: &VariableLayout, name: &str| {
+		if !var.categories().any(|c| c == category) {
+			return;
+		}
+		let Some(layout) = var.type_layout() else {
+			return;
+		};
+
+		let location = var.offset(category);
+		let semantic = Semantic::of(var, location);
+		match (layout.kind(), layout.element_type_layout()) {
+			(TypeKind::MeshOutput, Some(element)) => {
+				varyings.add_type(element, format!("{name}[]"), location, semantic)
+			}
+			(TypeKind::Array, Some(element)) if per_vertex => {
+				varyings.add_type(element, format!("{name}[]"), location, semantic)
+			}
+			(TypeKind::OutputStream, Some(element)) => {
+				varyings.add_type(element, name.to_string(), location, semantic)
+			}
+			_ => varyings.add_type(layout, name.to_string(), location, semantic),
+		}
+	};
+
+	for parameter in entry_point.parameters() {
+		add(parameter, parameter.name().unwrap_or_default());
+	}
+	if category == ParameterCategory::VaryingOutput
+		&& let Some(result) = entry_point.result_var_layout()
+	{
+		add(result, "return");
+	}
+	varyings.out
+}
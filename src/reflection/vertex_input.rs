@@ -0,0 +1,241 @@
+use super::{TypeLayout, VariableLayout};
+use crate::{ParameterCategory, ScalarType, TypeKind};
+
+const VARYING_INPUT: ParameterCategory = ParameterCategory::VaryingInput;
+
+/// A single vertex attribute consumed by a vertex entry point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VertexAttribute {
+	/// Dotted path from the entry point parameter, e.g. `input.material.uv`.
+	pub name: String,
+	pub location: u32,
+	/// Semantic name as written in the shader. Fields without their own semantic inherit the one of the closest
+	/// enclosing parameter or field, with the index advanced by their location.
+	pub semantic_name: Option<String>,
+	pub semantic_index: usize,
+	pub scalar_type: ScalarType,
+	pub component_count: u32,
+	/// A suggested vertex buffer format, if one matches the attribute exactly.
+	pub format: Option<VertexFormat>,
+}
+
+/// Vertex buffer formats, using the variant names of `GPUVertexFormat`.
+///
+/// Only formats that are read without conversion are suggested, so normalized formats are left to the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VertexFormat {
+	Uint8x2,
+	Uint8x4,
+	Sint8x2,
+	Sint8x4,
+	Uint16x2,
+	Uint16x4,
+	Sint16x2,
+	Sint16x4,
+	Float16x2,
+	Float16x4,
+	Float32,
+	Float32x2,
+	Float32x3,
+	Float32x4,
+	Uint32,
+	Uint32x2,
+	Uint32x3,
+	Uint32x4,
+	Sint32,
+	Sint32x2,
+	Sint32x3,
+	Sint32x4,
+}
+
+impl VertexFormat {
+	pub fn new(scalar_type: ScalarType, component_count: u32) -> Option<Self> {
+		use VertexFormat::*;
+		Some(match (scalar_type, component_count) {
+			(ScalarType::Uint8, 2) => Uint8x2,
+			(ScalarType::Uint8, 4) => Uint8x4,
+			(ScalarType::Int8, 2) => Sint8x2,
+			(ScalarType::Int8, 4) => Sint8x4,
+			(ScalarType::Uint16, 2) => Uint16x2,
+			(ScalarType::Uint16, 4) => Uint16x4,
+			(ScalarType::Int16, 2) => Sint16x2,
+			(ScalarType::Int16, 4) => Sint16x4,
+			(ScalarType::Float16, 2) => Float16x2,
+			(ScalarType::Float16, 4) => Float16x4,
+			(ScalarType::Float32, 1) => Float32,
+			(ScalarType::Float32, 2) => Float32x2,
+			(ScalarType::Float32, 3) => Float32x3,
+			(ScalarType::Float32, 4) => Float32x4,
+			(ScalarType::Uint32 | ScalarType::Bool, 1) => Uint32,
+			(ScalarType::Uint32 | ScalarType::Bool, 2) => Uint32x2,
+			(ScalarType::Uint32 | ScalarType::Bool, 3) => Uint32x3,
+			(ScalarType::Uint32 | ScalarType::Bool, 4) => Uint32x4,
+			(ScalarType::Int32, 1) => Sint32,
+			(ScalarType::Int32, 2) => Sint32x2,
+			(ScalarType::Int32, 3) => Sint32x3,
+			(ScalarType::Int32, 4) => Sint32x4,
+			_ => return None,
+		})
+	}
+
+	/// Size of one attribute value in bytes.
+	pub fn size(self) -> usize {
+		use VertexFormat::*;
+		match self {
+			Uint8x2 | Sint8x2 => 2,
+			Uint8x4 | Sint8x4 | Uint16x2 | Sint16x2 | Float16x2 | Float32 | Uint32 | Sint32 => 4,
+			Uint16x4 | Sint16x4 | Float16x4 | Float32x2 | Uint32x2 | Sint32x2 => 8,
+			Float32x3 | Uint32x3 | Sint32x3 => 12,
+			Float32x4 | Uint32x4 | Sint32x4 => 16,
+		}
+	}
+}
+
+/// The closest semantic seen while descending into a parameter.
+#[derive(Clone, Copy)]
+pub(super) struct Semantic<'a> {
+	name: &'a str,
+	index: usize,
+	location: usize,
+}
+
+impl<'a> Semantic<'a> {
+	pub fn of(var: &'a VariableLayout, location: usize) -> Option<Self> {
+		Some(Self {
+			name: var.semantic_name()?,
+			index: var.semantic_index(),
+			location,
+		})
+	}
+}
+
+pub(super) fn collect<'a>(
+	parameters: impl Iterator<Item = &'a VariableLayout>,
+) -> Vec<VertexAttribute> {
+	let mut varyings = Varyings::new(VARYING_INPUT);
+	for parameter in parameters {
+		if let Some(name) = parameter.name() {
+			varyings.add_variable(parameter, name.to_string(), 0, None);
+		}
+	}
+	varyings.out
+}
+
+/// Flattens varying parameters of one category into attributes.
+pub(super) struct Varyings {
+	category: ParameterCategory,
+	pub out: Vec<VertexAttribute>,
+}
+
+impl Varyings {
+	pub fn new(category: ParameterCategory) -> Self {
+		Self {
+			category,
+			out: Vec::new(),
+		}
+	}
+
+	pub fn add_variable<'a>(
+		&mut self,
+		var: &'a VariableLayout,
+		path: String,
+		base: usize,
+		inherited: Option<Semantic<'a>>,
+	) {
+		// System values such as `SV_VertexID` do not consume a location.
+		if !var.categories().any(|c| c == self.category) {
+			return;
+		}
+		let Some(layout) = var.type_layout() else {
+			return;
+		};
+
+		let location = base + var.offset(self.category);
+		let semantic = Semantic::of(var, location).or(inherited);
+		self.add_type(layout, path, location, semantic);
+	}
+
+	pub fn add_type<'a>(
+		&mut self,
+		layout: &'a TypeLayout,
+		path: String,
+		location: usize,
+		semantic: Option<Semantic<'a>>,
+	) {
+		match layout.kind() {
+			TypeKind::Struct => {
+				for field in layout.fields() {
+					if let Some(name) = field.name() {
+						self.add_variable(field, format!("{path}.{name}"), location, semantic);
+					}
+				}
+			}
+			TypeKind::Array => {
+				let Some(element) = layout.element_type_layout() else {
+					return;
+				};
+				let stride = element.size(self.category).max(1);
+				for i in 0..layout.element_count().unwrap_or(0) {
+					self.add_type(
+						element,
+						format!("{path}[{i}]"),
+						location + i * stride,
+						semantic,
+					);
+				}
+			}
+			TypeKind::Matrix => {
+				// Each row or column of a matrix takes its own location.
+				let rows = layout.row_count().unwrap_or(0);
+				let columns = layout.column_count().unwrap_or(0);
+				let locations = layout.size(self.category).max(1);
+				let component_count = (rows * columns) / locations as u32;
+				for i in 0..locations {
+					push(
+						&mut self.out,
+						layout,
+						format!("{path}[{i}]"),
+						location + i,
+						semantic,
+						component_count,
+					);
+				}
+			}
+			TypeKind::Vector => {
+				let component_count = layout.element_count().unwrap_or(0) as u32;
+				push(
+					&mut self.out,
+					layout,
+					path,
+					location,
+					semantic,
+					component_count,
+				);
+			}
+			TypeKind::Scalar => push(&mut self.out, layout, path, location, semantic, 1),
+			_ => {}
+		}
+	}
+}
+
+fn push(
+	out: &mut Vec<VertexAttribute>,
+	layout: &TypeLayout,
+	name: String,
+	location: usize,
+	semantic: Option<Semantic>,
+	component_count: u32,
+) {
+	let scalar_type = layout.scalar_type().unwrap_or(ScalarType::None);
+	out.push(VertexAttribute {
+		name,
+		location: location as u32,
+		semantic_name: semantic.map(|s| s.name.to_string()),
+		semantic_index: semantic.map_or(0, |s| s.index + (location - s.location)),
+		scalar_type,
+		component_count,
+		format: VertexFormat::new(scalar_type, component_count),
+	});
+}
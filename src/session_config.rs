@@ -0,0 +1,75 @@
+use crate::{
+	CompileTarget, CompilerOptions, Error, GlobalSession, Result, Session, SessionDesc, TargetDesc,
+};
+use std::ffi::CString;
+use std::path::PathBuf;
+
+/// The targets and search paths of a session, kept in owned form so sessions can be created from them repeatedly.
+///
+/// Unlike [`SessionDesc`], this does not borrow any strings and can be shared between threads.
+#[derive(Clone, Debug, Default)]
+pub struct SessionConfig {
+	targets: Vec<(CompileTarget, Option<String>)>,
+	search_paths: Vec<PathBuf>,
+}
+
+impl SessionConfig {
+	/// Adds a target with an optional profile name such as `spirv_1_5` or `sm_6_5`.
+	pub fn target(mut self, format: CompileTarget, profile: Option<&str>) -> Self {
+		self.targets.push((format, profile.map(str::to_string)));
+		self
+	}
+
+	pub fn search_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.search_paths.push(path.into());
+		self
+	}
+
+	pub fn search_paths(&self) -> &[PathBuf] {
+		&self.search_paths
+	}
+
+	pub fn target_count(&self) -> usize {
+		self.targets.len()
+	}
+
+	/// Creates a session with `options` applied to the session and to every target.
+	///
+	/// Fails if a profile name is not known to Slang.
+	pub fn create_session(
+		&self,
+		global_session: &GlobalSession,
+		options: &CompilerOptions,
+	) -> Result<Session> {
+		let mut targets = Vec::with_capacity(self.targets.len());
+		for (format, profile) in &self.targets {
+			let mut target = TargetDesc::default().format(*format).options(options);
+			if let Some(profile) = profile {
+				let profile_id = global_session.find_profile(profile)?;
+				if profile_id.is_unknown() {
+					return Err(Error::message(format!("unknown profile `{profile}`")));
+				}
+				target = target.profile(profile_id);
+			}
+			targets.push(target);
+		}
+
+		let search_paths_storage = self
+			.search_paths
+			.iter()
+			.map(|p| CString::new(p.to_string_lossy().as_bytes()).map_err(Error::InvalidString))
+			.collect::<Result<Vec<_>>>()?;
+		let search_paths = search_paths_storage
+			.iter()
+			.map(|s| s.as_ptr())
+			.collect::<Vec<_>>();
+
+		let session_desc = SessionDesc::default()
+			.targets(&targets)
+			.search_paths(&search_paths)
+			.options(options);
+		global_session
+			.create_session(&session_desc)
+			.ok_or(Error::Code(crate::E_FAIL))
+	}
+}
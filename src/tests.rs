@@ -37,6 +37,20 @@ fn obtain_test_session(
 	global_session.create_session(&session_desc)
 }
 
+fn link_test_program(
+	session: &slang::Session,
+	module: &slang::Module,
+	entry_point: &slang::EntryPoint,
+) -> slang::ComponentType {
+	let program = session
+		.create_composite_component_type(&[
+			module.downcast().clone(),
+			entry_point.downcast().clone(),
+		])
+		.unwrap();
+	program.link().unwrap().into_value()
+}
+
 //////
 //
 // Actual tests
@@ -169,3 +183,1384 @@ fn com_impls_blob() {
 		"The compiled programs should be identical"
 	);
 }
+
+#[test]
+fn parse_diagnostics() {
+	let output = "shaders/test.slang(9): error 30015: undefined identifier 'foo'.\n\
+		\toutput[index] = foo[index];\n\
+		\t                ^~~\n\
+		shaders/test.slang(2): note: see declaration of 'output'\n\
+		shaders/test.slang(4,7): warning 30081: implicit conversion from 'int' to 'float'.\n\
+		error 1: cannot open file 'missing.slang'.\n";
+
+	let diagnostics = slang::diagnostics::parse_diagnostics(output);
+	assert_eq!(diagnostics.len(), 3);
+
+	let error = &diagnostics[0];
+	assert_eq!(error.severity, slang::Severity::Error);
+	assert_eq!(error.code, Some(30015));
+	assert_eq!(error.path.as_deref(), Some("shaders/test.slang"));
+	assert_eq!(
+		error.span,
+		Some(slang::diagnostics::Span {
+			line: 9,
+			column: Some(18),
+			end_column: Some(21),
+		})
+	);
+	assert_eq!(error.message, "undefined identifier 'foo'.");
+	assert_eq!(error.notes.len(), 1);
+	assert_eq!(error.notes[0].severity, slang::Severity::Note);
+	assert_eq!(error.notes[0].span.map(|s| s.line), Some(2));
+
+	let warning = &diagnostics[1];
+	assert!(warning.is_warning());
+	assert_eq!(warning.span.and_then(|s| s.column), Some(7));
+
+	let unlocated = &diagnostics[2];
+	assert_eq!(unlocated.code, Some(1));
+	assert_eq!(unlocated.path, None);
+}
+
+#[test]
+fn compile_warnings() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"warnings",
+			"warnings.slang",
+			r#"RWStructuredBuffer<int> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	int truncated = 1.5;
+	output[id.x] = truncated;
+}
+"#,
+		)
+		.unwrap();
+
+	// Implicit conversion from 'float' to 'int' is not recommended.
+	let warning = module.warnings().find(|w| w.code == Some(30081)).unwrap();
+	assert_eq!(warning.severity, slang::Severity::Warning);
+	assert_eq!(warning.span.map(|s| s.line), Some(6));
+
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let code = linked_program.entry_point_code(0, 0).unwrap();
+	assert!(!code.as_slice().is_empty());
+	assert!(code.diagnostics.iter().all(|d| !d.is_error()));
+}
+
+#[cfg(feature = "com_impls")]
+#[test]
+fn com_impls_file_system() {
+	// Serve a module from memory that imports the on-disk test shader through the overlay
+	let file_system = slang::OverlayFileSystem::new(
+		slang::MemoryFileSystem::new().with_file(
+			"embedded/wrapper.slang",
+			"import test;\nfloat twice(float x) { return 2.0 * x; }\n",
+		),
+		slang::OsFileSystem::new("."),
+	);
+	let file_system = slang::ComPtr::new(slang::FileSystemAdapter::from_file_system(file_system));
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let target_desc = slang::TargetDesc::default()
+		.format(slang::CompileTarget::Spirv)
+		.profile(global_session.find_profile("glsl_450").unwrap());
+	let targets = [target_desc];
+	let search_paths_storage = [
+		std::ffi::CString::new("embedded").unwrap(),
+		std::ffi::CString::new("shaders").unwrap(),
+	];
+	let search_paths = search_paths_storage
+		.iter()
+		.map(|s| s.as_ptr())
+		.collect::<Vec<_>>();
+
+	let session_desc = slang::SessionDesc::default()
+		.targets(&targets)
+		.search_paths(&search_paths)
+		.file_system(&file_system);
+	let session = global_session.create_session(&session_desc).unwrap();
+
+	let module = session.load_module("wrapper").unwrap();
+	assert_eq!(module.name(), "wrapper");
+	assert!(
+		module
+			.dependency_file_paths()
+			.any(|path| path.ends_with("test.slang"))
+	);
+}
+
+#[test]
+fn program_layout() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+
+	let linked_program = link_test_program(&session, &module, &entry_point);
+
+	// The owning layout handle stays valid after everything it was obtained from is gone.
+	let program_layout = linked_program.program_layout(0).unwrap();
+	drop((entry_point, module, linked_program, session));
+	assert_eq!(program_layout.entry_point_count(), 1);
+	assert_eq!(program_layout.parameter_count(), 3);
+}
+
+#[test]
+fn reflection_snapshot() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+
+	let linked_program = link_test_program(&session, &module, &entry_point);
+
+	let snapshot = linked_program.layout(0).unwrap().snapshot();
+	drop((entry_point, module, linked_program, session));
+
+	let names = snapshot
+		.parameters
+		.iter()
+		.map(|p| p.name.as_deref().unwrap())
+		.collect::<Vec<_>>();
+	assert_eq!(names, ["input_0", "input_1", "output"]);
+
+	let output = snapshot.find_parameter_by_name("output").unwrap();
+	assert_eq!(output.type_layout.kind, slang::TypeKind::Resource);
+	assert_eq!(
+		output
+			.type_layout
+			.element()
+			.and_then(|e| e.ty.as_ref())
+			.map(|t| t.scalar_type),
+		Some(slang::ScalarType::Float32)
+	);
+
+	let main = snapshot.find_entry_point_by_name("main").unwrap();
+	assert_eq!(main.stage, slang::Stage::Compute);
+	assert_eq!(main.compute_thread_group_size, [1, 1, 1]);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn reflection_json_roundtrip() {
+	// In the format written by `slangc -reflection-json`
+	let json = r#"{
+		"parameters": [
+			{
+				"name": "input_0",
+				"binding": {"kind": "descriptorTableSlot", "index": 0},
+				"type": {
+					"kind": "resource",
+					"baseShape": "structuredBuffer",
+					"resultType": {"kind": "scalar", "scalarType": "float32"}
+				}
+			},
+			{
+				"name": "output",
+				"binding": {"kind": "descriptorTableSlot", "space": 1, "index": 2},
+				"type": {
+					"kind": "resource",
+					"baseShape": "structuredBuffer",
+					"access": "readWrite",
+					"resultType": {"kind": "scalar", "scalarType": "float32"}
+				}
+			}
+		],
+		"entryPoints": [
+			{
+				"name": "main",
+				"stage": "compute",
+				"parameters": [
+					{
+						"name": "thread_id",
+						"semanticName": "SV_DISPATCHTHREADID",
+						"type": {
+							"kind": "vector",
+							"elementCount": 3,
+							"elementType": {"kind": "scalar", "scalarType": "uint32"}
+						}
+					}
+				],
+				"threadGroupSize": [1, 1, 1]
+			}
+		],
+		"hashedStrings": {
+			"hello": 1706418452
+		}
+	}"#;
+
+	let shader = slang::reflection::snapshot::Shader::from_reflection_json(json).unwrap();
+	let output = shader.find_parameter_by_name("output").unwrap();
+	assert_eq!(
+		output.binding_space_with_category(slang::ParameterCategory::DescriptorTableSlot),
+		1
+	);
+	assert_eq!(
+		output.type_layout.ty.as_ref().unwrap().resource_access,
+		slang::ResourceAccess::ReadWrite
+	);
+	assert_eq!(shader.entry_points[0].compute_thread_group_size, [1, 1, 1]);
+	assert_eq!(shader.hashed_strings[0].hash, 1706418452);
+
+	let written: serde_json::Value = serde_json::from_str(&shader.to_reflection_json()).unwrap();
+	let expected: serde_json::Value = serde_json::from_str(json).unwrap();
+	assert_eq!(written, expected);
+}
+
+#[test]
+fn reflection_codegen() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"codegen",
+			"codegen.slang",
+			r#"
+struct Params {
+	float3 direction;
+	float intensity;
+	float weights[2];
+};
+ConstantBuffer<Params> params;
+
+struct Keywords {
+	float type;
+	float self;
+	float Self;
+	float super;
+	float crate;
+	float become;
+	float priv;
+};
+ConstantBuffer<Keywords> keywords;
+
+struct Padded {
+	float _pad0;
+	float3 direction;
+};
+ConstantBuffer<Padded> padded;
+"#,
+		)
+		.unwrap();
+	let program = module.downcast().link().unwrap();
+	let layout = program.layout(0).unwrap();
+
+	let keywords = layout.parameters().nth(1).unwrap();
+	let source = slang::reflection::codegen::generate_rust_struct(
+		"Keywords",
+		keywords.type_layout().unwrap(),
+	)
+	.unwrap();
+	for field in [
+		"r#type", "self_", "Self_", "super_", "crate_", "r#become", "r#priv",
+	] {
+		assert!(source.contains(&format!("pub {field}: f32,")));
+	}
+
+	let mut generator =
+		slang::reflection::codegen::StructGenerator::new().derive("shader_slang::ShaderLayout");
+	generator
+		.add("Keywords", keywords.type_layout().unwrap())
+		.unwrap();
+	let source = generator.generate();
+	assert!(source.contains("\t#[slang(name = \"self\")]\n\tpub self_: f32,"));
+	assert!(!source.contains("#[slang(name = \"type\")]"));
+
+	let padded = layout.parameters().nth(2).unwrap();
+	let source =
+		slang::reflection::codegen::generate_rust_struct("Padded", padded.type_layout().unwrap())
+			.unwrap();
+	assert!(source.contains("pub _pad0: f32,"));
+	assert!(source.contains("pub _pad1: [u8; 12],"));
+
+	let params = layout.parameters().next().unwrap();
+	assert!(
+		generator
+			.add("Keywords", params.type_layout().unwrap())
+			.is_err()
+	);
+
+	let source =
+		slang::reflection::codegen::generate_rust_struct("Params", params.type_layout().unwrap())
+			.unwrap();
+
+	// Vulkan constant buffers use std140, which pads array elements to 16 bytes.
+	assert!(source.contains("pub struct Params {"));
+	assert!(source.contains("pub direction: [f32; 3],"));
+	assert!(source.contains("pub weights: [ParamsWeightsPadded; 2],"));
+	assert!(source.contains("offset_of!(Params, intensity) == 12"));
+	assert!(source.contains("size_of::<Params>() == 48"));
+}
+
+#[test]
+fn reflection_shader_cursor() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"cursor",
+			"cursor.slang",
+			r#"
+struct Light {
+	float3 color;
+	float intensity;
+};
+struct Params {
+	Light lights[4];
+	float4x4 view;
+	float exposure;
+	float3x3 normal;
+	float2x2 rotation;
+};
+ConstantBuffer<Params> params;
+"#,
+		)
+		.unwrap();
+	let program = module.downcast().link().unwrap();
+	let layout = program.layout(0).unwrap();
+	let params = layout.parameters().next().unwrap().type_layout().unwrap();
+
+	let mut data = vec![0u8; 224];
+	let mut cursor = slang::reflection::ShaderCursor::new(&mut data, params).unwrap();
+	cursor
+		.path("lights[3].color")
+		.unwrap()
+		.write_vec(&[1.0f32, 2.0, 3.0])
+		.unwrap();
+	cursor.field("exposure").unwrap().write_f32(0.5).unwrap();
+
+	let mut view = [[0.0f32; 4]; 4];
+	view[0][1] = 7.0;
+	cursor.field("view").unwrap().write_matrix(&view).unwrap();
+
+	// Every row of these is padded to 16 bytes.
+	let normal_offset = cursor.field("normal").unwrap().offset();
+	let mut normal = [[0.0f32; 3]; 3];
+	normal[2][1] = 5.0;
+	cursor
+		.field("normal")
+		.unwrap()
+		.write_matrix(&normal)
+		.unwrap();
+	let rotation_offset = cursor.field("rotation").unwrap().offset();
+	let rotation = [[1.0f32, 2.0], [3.0, 4.0]];
+	cursor
+		.field("rotation")
+		.unwrap()
+		.write_matrix(&rotation)
+		.unwrap();
+
+	assert!(cursor.path("lights[4]").is_err());
+	assert!(cursor.field("exposure").unwrap().write_u32(1).is_err());
+
+	let read = |offset: usize| f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+	assert_eq!([read(48), read(52), read(56)], [1.0, 2.0, 3.0]);
+	// The test session uses row-major matrices, so row 0, column 1 is the second float.
+	assert_eq!(read(64 + 4), 7.0);
+	assert_eq!(read(128), 0.5);
+	assert_eq!(read(normal_offset + 2 * 16 + 4), 5.0);
+	assert_eq!(
+		[
+			read(rotation_offset),
+			read(rotation_offset + 4),
+			read(rotation_offset + 16),
+			read(rotation_offset + 20)
+		],
+		[1.0, 2.0, 3.0, 4.0]
+	);
+}
+
+#[test]
+fn reflection_bindings() {
+	use slang::reflection::bindings::{DescriptorType, PipelineLayoutDesc, StageFlags};
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"bindings",
+			"bindings.slang",
+			r#"
+struct Material {
+	float4 tint;
+	Texture2D albedo;
+	SamplerState linearSampler;
+};
+struct Constants {
+	uint count;
+};
+
+RWStructuredBuffer<float> output;
+ParameterBlock<Material> material;
+[[vk::push_constant]] ConstantBuffer<Constants> constants;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	output[id.x] = material.albedo.SampleLevel(material.linearSampler, float2(0), 0).x * material.tint.x + constants.count;
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let layout = PipelineLayoutDesc::new(linked_program.layout(0).unwrap());
+
+	let globals = layout.descriptor_set(0).unwrap();
+	assert_eq!(globals.bindings.len(), 1);
+	assert_eq!(
+		globals.bindings[0].descriptor_type,
+		DescriptorType::StorageBuffer
+	);
+	assert_eq!(globals.bindings[0].stages, StageFlags::COMPUTE);
+
+	// The parameter block gets its own set, with its uniforms in an implicit constant buffer.
+	let material = layout.descriptor_set(1).unwrap();
+	let types = material
+		.bindings
+		.iter()
+		.map(|b| (b.binding, b.descriptor_type))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		types,
+		[
+			(0, DescriptorType::UniformBuffer),
+			(1, DescriptorType::SampledImage),
+			(2, DescriptorType::Sampler),
+		]
+	);
+
+	assert_eq!(layout.push_constant_ranges.len(), 1);
+	assert_eq!(layout.push_constant_ranges[0].size, 4);
+}
+
+#[test]
+fn reflection_webgpu_bindings() {
+	use slang::reflection::bindings::webgpu::*;
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"webgpu",
+			"webgpu.slang",
+			r#"
+struct Params {
+	float4 scale;
+};
+
+ConstantBuffer<Params> params;
+Texture2DArray<float4> layers;
+StructuredBuffer<float2> points;
+[format("rgba8")] RWTexture2D<float4> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	output[id.xy] = layers.Load(int4(id, 0)) * params.scale + points[id.x].x;
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let groups = bind_group_layouts(linked_program.layout(0).unwrap());
+
+	assert_eq!(groups.len(), 1);
+	let kinds = groups[0].entries.iter().map(|e| e.ty).collect::<Vec<_>>();
+	assert_eq!(
+		kinds,
+		[
+			BindingKind::Buffer {
+				ty: BufferBindingType::Uniform,
+				has_dynamic_offset: false,
+				min_binding_size: std::num::NonZeroU64::new(16),
+			},
+			BindingKind::Texture {
+				sample_type: TextureSampleType::Float { filterable: true },
+				view_dimension: TextureViewDimension::D2Array,
+				multisampled: false,
+			},
+			BindingKind::Buffer {
+				ty: BufferBindingType::Storage { read_only: true },
+				has_dynamic_offset: false,
+				min_binding_size: std::num::NonZeroU64::new(8),
+			},
+			BindingKind::StorageTexture {
+				access: StorageTextureAccess::ReadWrite,
+				format: Some(TextureFormat::Rgba8Unorm),
+				view_dimension: TextureViewDimension::D2,
+			},
+		]
+	);
+	assert!(
+		groups[0]
+			.entries
+			.iter()
+			.all(|e| e.visibility == ShaderStages::COMPUTE)
+	);
+}
+
+#[test]
+fn reflection_d3d12_root_signature() {
+	use slang::reflection::bindings::d3d12::{RangeType, RootParameter, RootSignatureDesc};
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let target_desc = slang::TargetDesc::default()
+		.format(slang::CompileTarget::Hlsl)
+		.profile(global_session.find_profile("sm_5_1").unwrap());
+	let targets = [target_desc];
+	let session_desc = slang::SessionDesc::default().targets(&targets);
+	let session = global_session.create_session(&session_desc).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"d3d12",
+			"d3d12.slang",
+			r#"
+[__AttributeUsage(_AttributeTargets.Var)]
+struct StaticSamplerAttribute {
+	string filter;
+	string addressMode;
+};
+
+Texture2D albedo;
+[StaticSampler("MIN_MAG_MIP_POINT", "CLAMP")]
+SamplerState pointSampler;
+RWStructuredBuffer<float> output;
+uniform float exposure;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID, uniform float scale) {
+	output[id.x] = albedo.SampleLevel(pointSampler, float2(0), 0).x * scale * exposure;
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let desc = RootSignatureDesc::new(linked_program.layout(0).unwrap());
+
+	let RootParameter::Constants(constants) = &desc.parameters[0] else {
+		panic!("expected root constants, got {:?}", desc.parameters);
+	};
+	assert_eq!(constants.num_32bit_values, 1);
+
+	let RootParameter::DescriptorTable(table) = &desc.parameters[1] else {
+		panic!("expected a descriptor table, got {:?}", desc.parameters);
+	};
+	let ranges = table
+		.ranges
+		.iter()
+		.map(|r| (r.range_type, r.base_register))
+		.collect::<Vec<_>>();
+	// The loose `exposure` is placed in the `$Globals` constant buffer.
+	assert_eq!(
+		ranges,
+		[
+			(RangeType::Cbv, 0),
+			(RangeType::Srv, 0),
+			(RangeType::Uav, 0)
+		]
+	);
+
+	assert_eq!(desc.static_samplers.len(), 1);
+	let root_signature = desc.to_hlsl_string();
+	assert!(root_signature.contains(
+		"StaticSampler(s0, space=0, filter=FILTER_MIN_MAG_MIP_POINT, addressU=TEXTURE_ADDRESS_CLAMP"
+	));
+	assert!(
+		root_signature
+			.contains("SRV(t0, numDescriptors=1, space=0), UAV(u0, numDescriptors=1, space=0)")
+	);
+}
+
+#[test]
+fn reflection_vertex_inputs() {
+	use slang::reflection::VertexFormat;
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"vertex_inputs",
+			"vertex_inputs.slang",
+			r#"
+struct Skin {
+	uint4 joints : JOINTS;
+	float4 weights : WEIGHTS;
+};
+
+struct Vertex {
+	float3 position : POSITION;
+	float2 uv : TEXCOORD0;
+	Skin skin;
+};
+
+[shader("vertex")]
+float4 main(Vertex input, uint vertexId : SV_VertexID) : SV_Position {
+	return float4(input.position + input.skin.weights.xyz * float(input.skin.joints.x + vertexId), input.uv.x);
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let reflection = linked_program.layout(0).unwrap();
+	let inputs = reflection.entry_point_by_index(0).unwrap().vertex_inputs();
+
+	let summary = inputs
+		.iter()
+		.map(|a| {
+			(
+				a.name.as_str(),
+				a.location,
+				a.semantic_name.as_deref(),
+				a.format,
+			)
+		})
+		.collect::<Vec<_>>();
+	assert_eq!(
+		summary,
+		[
+			(
+				"input.position",
+				0,
+				Some("POSITION"),
+				Some(VertexFormat::Float32x3)
+			),
+			(
+				"input.uv",
+				1,
+				Some("TEXCOORD"),
+				Some(VertexFormat::Float32x2)
+			),
+			(
+				"input.skin.joints",
+				2,
+				Some("JOINTS"),
+				Some(VertexFormat::Uint32x4)
+			),
+			(
+				"input.skin.weights",
+				3,
+				Some("WEIGHTS"),
+				Some(VertexFormat::Float32x4)
+			),
+		]
+	);
+}
+
+#[test]
+fn reflection_stage_interface() {
+	use slang::reflection::{StageInterfaceMismatchKind, check_stage_interface};
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"stage_interface",
+			"stage_interface.slang",
+			r#"
+struct VertexOutput {
+	float4 position : SV_Position;
+	float3 normal : NORMAL;
+	float2 uv : TEXCOORD;
+};
+
+struct FragmentInput {
+	float4 position : SV_Position;
+	float3 normal : NORMAL;
+	int2 uv : TEXCOORD;
+};
+
+[shader("vertex")]
+VertexOutput vertexMain(float3 position : POSITION) {
+	VertexOutput output;
+	output.position = float4(position, 1.0);
+	output.normal = position;
+	output.uv = position.xy;
+	return output;
+}
+
+[shader("fragment")]
+float4 fragmentMain(FragmentInput input) : SV_Target {
+	return float4(input.normal, float(input.uv.x));
+}
+"#,
+		)
+		.unwrap();
+	let vertex = module.find_entry_point_by_name("vertexMain").unwrap();
+	let fragment = module.find_entry_point_by_name("fragmentMain").unwrap();
+	let program = session
+		.create_composite_component_type(&[
+			module.downcast().clone(),
+			vertex.downcast().clone(),
+			fragment.downcast().clone(),
+		])
+		.unwrap();
+	let linked_program = program.link().unwrap();
+	let reflection = linked_program.layout(0).unwrap();
+
+	let mismatches = check_stage_interface(
+		reflection.entry_point_by_index(0).unwrap(),
+		reflection.entry_point_by_index(1).unwrap(),
+	);
+	assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+	assert_eq!(mismatches[0].name, "input.uv");
+	assert!(matches!(
+		mismatches[0].kind,
+		StageInterfaceMismatchKind::ScalarType { .. }
+	));
+}
+
+#[test]
+fn reflection_stage_interface_mesh() {
+	use slang::reflection::check_stage_interface;
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"stage_interface_mesh",
+			"stage_interface_mesh.slang",
+			r#"
+struct Vertex {
+	float4 position : SV_Position;
+	float3 color : COLOR;
+};
+
+[shader("mesh")]
+[numthreads(1, 1, 1)]
+[outputtopology("triangle")]
+void meshMain(out OutputVertices<Vertex, 3> vertices, out OutputIndices<uint3, 1> indices) {
+	SetMeshOutputCounts(3, 1);
+	for (uint i = 0; i < 3; i++) {
+		vertices[i].position = float4(float(i), 0.0, 0.0, 1.0);
+		vertices[i].color = float3(1.0, 0.0, 0.0);
+	}
+	indices[0] = uint3(0, 1, 2);
+}
+
+[shader("fragment")]
+float4 fragmentMain(Vertex input) : SV_Target {
+	return float4(input.color, 1.0);
+}
+"#,
+		)
+		.unwrap();
+	let mesh = module.find_entry_point_by_name("meshMain").unwrap();
+	let fragment = module.find_entry_point_by_name("fragmentMain").unwrap();
+	let program = session
+		.create_composite_component_type(&[
+			module.downcast().clone(),
+			mesh.downcast().clone(),
+			fragment.downcast().clone(),
+		])
+		.unwrap();
+	let linked_program = program.link().unwrap();
+	let reflection = linked_program.layout(0).unwrap();
+
+	let mismatches = check_stage_interface(
+		reflection.entry_point_by_index(0).unwrap(),
+		reflection.entry_point_by_index(1).unwrap(),
+	);
+	assert!(mismatches.is_empty(), "{mismatches:?}");
+}
+
+#[test]
+fn reflection_render_targets() {
+	use slang::reflection::DepthOutput;
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"render_targets",
+			"render_targets.slang",
+			r#"
+struct GBuffer {
+	float4 albedo : SV_Target0;
+	uint2 material : SV_Target2;
+	float depth : SV_Depth;
+};
+
+[shader("fragment")]
+GBuffer main(float4 position : SV_Position) {
+	GBuffer output;
+	output.albedo = position;
+	output.material = uint2(position.xy);
+	output.depth = position.z;
+	return output;
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+	let reflection = linked_program.layout(0).unwrap();
+	let targets = reflection.entry_point_by_index(0).unwrap().render_targets();
+
+	let color = targets
+		.color
+		.iter()
+		.map(|t| (t.index, t.scalar_type, t.component_count))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		color,
+		[
+			(0, slang::ScalarType::Float32, 4),
+			(2, slang::ScalarType::Uint32, 2),
+		]
+	);
+	assert_eq!(targets.slot_count(), 3);
+	assert_eq!(targets.depth, Some(DepthOutput::Any));
+}
+
+#[cfg(feature = "build")]
+#[test]
+fn build_directory() {
+	let out_dir = std::env::temp_dir().join(format!("slang-build-{}", std::process::id()));
+	std::fs::create_dir_all(&out_dir).unwrap();
+
+	let artifacts = slang::build::Build::new()
+		.directory("shaders")
+		.target(slang::CompileTarget::Spirv, "glsl_450")
+		.target(slang::CompileTarget::Spirv, "spirv_1_5")
+		.out_dir(&out_dir)
+		.compile()
+		.unwrap();
+
+	assert_eq!(artifacts.len(), 2);
+	assert_eq!(artifacts[0].module, "test");
+	assert_eq!(artifacts[0].entry_point, "main");
+	assert_ne!(artifacts[0].path, artifacts[1].path);
+	assert!(!std::fs::read(&artifacts[0].path).unwrap().is_empty());
+
+	let generated = std::fs::read_to_string(out_dir.join("shaders.rs")).unwrap();
+	assert!(generated.contains("pub mod test {"));
+	assert!(generated.contains("pub const MAIN_SPIRV_GLSL_450: &[u8] = include_bytes!("));
+	assert!(generated.contains("pub const MAIN_SPIRV_SPIRV_1_5: &[u8] = include_bytes!("));
+
+	// The same module name in two directories.
+	let error = slang::build::Build::new()
+		.directory("shaders")
+		.directory("shaders")
+		.target(slang::CompileTarget::Spirv, "glsl_450")
+		.out_dir(&out_dir)
+		.compile()
+		.unwrap_err();
+	assert!(matches!(
+		error,
+		slang::build::BuildError::DuplicateModule { .. }
+	));
+
+	// Module names that are Rust keywords are escaped, and entry points that map to the same constant are rejected.
+	let source_dir = out_dir.join("source");
+	std::fs::create_dir_all(&source_dir).unwrap();
+	let compute = |name: &str| {
+		format!(
+			"[shader(\"compute\")]\n[numthreads(1, 1, 1)]\nvoid {name}(uint3 id : SV_DispatchThreadID) {{}}\n"
+		)
+	};
+	std::fs::write(source_dir.join("type.slang"), compute("main")).unwrap();
+	slang::build::Build::new()
+		.directory(&source_dir)
+		.target(slang::CompileTarget::Spirv, "glsl_450")
+		.out_dir(&out_dir)
+		.compile()
+		.unwrap();
+	let generated = std::fs::read_to_string(out_dir.join("shaders.rs")).unwrap();
+	assert!(generated.contains("pub mod r#type {"));
+
+	std::fs::write(
+		source_dir.join("type.slang"),
+		compute("vertexMain") + &compute("vertex_main"),
+	)
+	.unwrap();
+	let error = slang::build::Build::new()
+		.directory(&source_dir)
+		.target(slang::CompileTarget::Spirv, "glsl_450")
+		.out_dir(&out_dir)
+		.compile()
+		.unwrap_err();
+	assert!(matches!(
+		error,
+		slang::build::BuildError::DuplicateConstant { ref name, .. } if name == "VERTEX_MAIN_SPIRV_GLSL_450"
+	));
+
+	std::fs::remove_dir_all(&out_dir).unwrap();
+}
+
+#[cfg(feature = "hot_reload")]
+#[test]
+fn hot_reload() {
+	use slang::SessionConfig;
+	use slang::hot_reload::HotReloader;
+	use std::time::{Duration, SystemTime};
+
+	let dir = std::env::temp_dir().join(format!("slang-hot-reload-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let common = dir.join("common.slang");
+	let write = |path: &std::path::Path, source: &str, seconds: u64| {
+		std::fs::write(path, source).unwrap();
+		// Set the time explicitly, since writes within the timer resolution would otherwise go unnoticed.
+		std::fs::File::options()
+			.write(true)
+			.open(path)
+			.unwrap()
+			.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+			.unwrap();
+	};
+
+	write(&common, "float scale() { return 2.0; }", 1);
+	write(
+		&dir.join("reload.slang"),
+		r#"
+import common;
+RWStructuredBuffer<float> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	output[id.x] = scale();
+}
+"#,
+		1,
+	);
+
+	let config = SessionConfig::default()
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.search_path(&dir);
+	let mut reloader = HotReloader::new(config).unwrap();
+	let id = reloader.add_program("reload", &["main"]).unwrap();
+	assert!(reloader.poll().is_empty());
+
+	// A change to an imported module recompiles the program.
+	write(&common, "float scale() { return 3.0; }", 2);
+	let reloads = reloader.poll();
+	assert_eq!(reloads.len(), 1);
+	assert!(reloads[0].result.is_ok());
+
+	// A broken edit reports diagnostics once and keeps the last good program.
+	let code = reloader
+		.program(id)
+		.entry_point_code(0, 0)
+		.unwrap()
+		.as_slice()
+		.to_vec();
+	write(&common, "float scale() { return undefined; }", 3);
+	let reloads = reloader.poll();
+	assert_eq!(reloads.len(), 1);
+	let error = reloads[0].result.as_ref().unwrap_err();
+	assert!(error.diagnostics().iter().any(|d| d.is_error()));
+	assert_eq!(
+		reloader
+			.program(id)
+			.entry_point_code(0, 0)
+			.unwrap()
+			.as_slice(),
+		code
+	);
+	assert!(reloader.poll().is_empty());
+
+	// Files first imported by a failed compilation are watched as well.
+	let extra = dir.join("extra.slang");
+	write(&extra, "float offset() { return undefined; }", 4);
+	write(
+		&dir.join("reload.slang"),
+		r#"
+import common;
+import extra;
+RWStructuredBuffer<float> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	output[id.x] = scale() + offset();
+}
+"#,
+		4,
+	);
+	write(&common, "float scale() { return 3.0; }", 4);
+	let reloads = reloader.poll();
+	assert_eq!(reloads.len(), 1);
+	assert!(reloads[0].result.is_err());
+
+	write(&extra, "float offset() { return 1.0; }", 5);
+	let reloads = reloader.poll();
+	assert_eq!(reloads.len(), 1);
+	assert!(reloads[0].result.is_ok());
+	assert!(reloader.poll().is_empty());
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn target_artifacts() {
+	let global_session = slang::GlobalSession::new().unwrap();
+
+	let spirv_profile = global_session.find_profile("glsl_450").unwrap();
+	let hlsl_profile = global_session.find_profile("sm_5_0").unwrap();
+	let session = slang::SessionConfig::default()
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.target(slang::CompileTarget::Hlsl, Some("sm_5_0"))
+		.search_path("shaders")
+		.create_session(&global_session, &slang::CompilerOptions::default())
+		.unwrap();
+
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+
+	assert_eq!(
+		session.targets(),
+		[
+			slang::TargetKey {
+				format: slang::CompileTarget::Spirv,
+				profile: spirv_profile,
+			},
+			slang::TargetKey {
+				format: slang::CompileTarget::Hlsl,
+				profile: hlsl_profile,
+			},
+		]
+	);
+	let artifacts = linked_program.target_artifacts().unwrap();
+	assert_eq!(artifacts.len(), 2);
+
+	let spirv = &artifacts[&slang::TargetKey {
+		format: slang::CompileTarget::Spirv,
+		profile: spirv_profile,
+	}];
+	assert_eq!(spirv.target_index, 0);
+	let main = spirv.entry_point("main").unwrap();
+	assert_eq!(main.stage, slang::Stage::Compute);
+	assert_eq!(&main.code.as_slice()[..4], &0x07230203u32.to_le_bytes());
+
+	let hlsl = &artifacts[&slang::TargetKey {
+		format: slang::CompileTarget::Hlsl,
+		profile: hlsl_profile,
+	}];
+	assert!(
+		hlsl.entry_points[0]
+			.code
+			.as_str()
+			.unwrap()
+			.contains("numthreads")
+	);
+
+	// Artifacts of targets with the same format and profile cannot be told apart.
+	let spirv_twice = slang::SessionConfig::default()
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.search_path("shaders")
+		.create_session(&global_session, &slang::CompilerOptions::default())
+		.unwrap();
+	let module = spirv_twice.load_module("test.slang").unwrap();
+	let linked_program = module.downcast().link().unwrap();
+	assert!(linked_program.target_artifacts().is_err());
+}
+
+#[cfg(feature = "variants")]
+#[test]
+fn variants() {
+	use slang::variants::VariantCompiler;
+
+	let dir = std::env::temp_dir().join(format!("slang-variants-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	std::fs::write(
+		dir.join("variants.slang"),
+		r#"
+RWStructuredBuffer<float> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	output[id.x] = float(id.x) * SCALE;
+}
+"#,
+	)
+	.unwrap();
+
+	let variants = VariantCompiler::new("variants")
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.search_path(&dir)
+		.axis("SCALE", [1, 2, 3])
+		.axis("UNUSED", [0, 1])
+		.filter(|defines| defines.get("SCALE") != Some("3"))
+		.threads(2)
+		.compile()
+		.unwrap();
+
+	// `UNUSED` does not change the output, so only `SCALE` produces distinct variants.
+	assert_eq!(variants.len(), 4);
+	assert_eq!(variants.unique().len(), 2);
+	assert!(variants.get(&[("SCALE", "3"), ("UNUSED", "0")]).is_none());
+
+	let a = variants.get(&[("UNUSED", "0"), ("SCALE", "2")]).unwrap();
+	let b = variants.get(&[("UNUSED", "1"), ("SCALE", "2")]).unwrap();
+	assert_eq!(a.hash, b.hash);
+	assert_eq!(a.entry_points, ["main"]);
+	assert!(a.reflection[0].find_parameter_by_name("output").is_some());
+	assert!(variants.get(&[("SCALE", "2"), ("SCALE", "2")]).is_none());
+
+	let error = VariantCompiler::new("variants")
+		.target(slang::CompileTarget::Spirv, Some("glsl_450"))
+		.search_path(&dir)
+		.axis("SCALE", [1])
+		.axis("SCALE", [2])
+		.compile()
+		.unwrap_err();
+	assert!(error.defines.0.is_empty());
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn shader_cache() {
+	use slang::cache::{CacheKey, ShaderCache};
+
+	let dir = std::env::temp_dir().join(format!("slang-cache-{}", std::process::id()));
+	let cache = ShaderCache::new(&dir).unwrap();
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let targets = [slang::TargetDesc::default()
+		.format(slang::CompileTarget::Spirv)
+		.profile(global_session.find_profile("glsl_450").unwrap())];
+	let search_path = std::ffi::CString::new("shaders").unwrap();
+	let search_paths = [search_path.as_ptr()];
+	let session_desc = slang::SessionDesc::default()
+		.targets(&targets)
+		.search_paths(&search_paths);
+	let session = global_session.create_session(&session_desc).unwrap();
+
+	let module = session.load_module("test.slang").unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let linked_program = link_test_program(&session, &module, &entry_point);
+
+	let key =
+		CacheKey::for_entry_point(&global_session, &session_desc, &linked_program, 0, 0).unwrap();
+	assert!(cache.get(&key).is_none());
+
+	let compiled = cache
+		.entry_point_code(&global_session, &session_desc, &linked_program, 0, 0)
+		.unwrap();
+	let cached = cache.get(&key).unwrap();
+	assert_eq!(cached, compiled);
+	assert!(
+		cached
+			.reflection
+			.unwrap()
+			.find_parameter_by_name("output")
+			.is_some()
+	);
+
+	// A limit smaller than the only entry evicts it.
+	let cache = cache.max_size(1);
+	cache.evict().unwrap();
+	assert!(cache.get(&key).is_none());
+	assert_eq!(cache.size().unwrap(), 0);
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "module_cache")]
+#[test]
+fn module_cache() {
+	use slang::module_cache::ModuleCache;
+
+	let dir = std::env::temp_dir().join(format!("slang-module-cache-{}", std::process::id()));
+	let cache = ModuleCache::new(&dir).unwrap();
+
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	assert!(!cache.is_up_to_date(&session, "test"));
+	cache.load_module(&session, "test").unwrap();
+
+	// A fresh session loads the module from the stored IR.
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	assert!(cache.is_up_to_date(&session, "test"));
+	let module = cache.load_module(&session, "test").unwrap();
+	assert!(module.find_entry_point_by_name("main").is_some());
+
+	// A session whose search paths resolve the name to another file does not use the stored IR.
+	let other_dir = dir.join("other");
+	std::fs::create_dir_all(&other_dir).unwrap();
+	std::fs::write(
+		other_dir.join("test.slang"),
+		"[shader(\"compute\")]\n[numthreads(1, 1, 1)]\nvoid other() {}\n",
+	)
+	.unwrap();
+	let other_session = obtain_test_session(&global_session, &[&other_dir]).unwrap();
+	assert!(!cache.is_up_to_date(&other_session, "test"));
+	let module = cache.load_module(&other_session, "test").unwrap();
+	assert!(module.find_entry_point_by_name("other").is_some());
+	assert!(cache.is_up_to_date(&session, "test"));
+
+	cache.clear().unwrap();
+	assert!(!cache.is_up_to_date(&session, "test"));
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn specialize() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"materials",
+			"materials.slang",
+			r#"
+interface IMaterial {
+	static float3 shade();
+};
+
+struct Red : IMaterial {
+	static float3 shade() { return float3(1, 0, 0); }
+};
+
+struct Green : IMaterial {
+	static float3 shade() { return float3(0, 1, 0); }
+};
+
+RWStructuredBuffer<float3> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main<M : IMaterial>() {
+	output[0] = M.shade();
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[
+			module.downcast().clone(),
+			entry_point.downcast().clone(),
+		])
+		.unwrap();
+	assert_eq!(program.specialization_param_count(), 1);
+
+	let layout = program.layout(0).unwrap();
+	let params = layout.specialization_params();
+	assert_eq!(params.len(), 1);
+	assert_eq!(
+		params[0].kind,
+		slang::reflection::SpecializationParamKind::Generic
+	);
+	assert_eq!(params[0].name, Some("M"));
+	assert_eq!(params[0].entry_point, Some("main"));
+	assert_eq!(params[0].constraints[0].name(), Some("IMaterial"));
+
+	let green = layout.find_type_by_name("Green").unwrap();
+	let mut code = Vec::new();
+	for arg in [
+		slang::SpecializationArg::Expr("Red"),
+		slang::SpecializationArg::Type(green),
+	] {
+		let specialized = program.specialize(&[arg]).unwrap();
+		assert_eq!(specialized.specialization_param_count(), 0);
+		let linked = specialized.link().unwrap();
+		code.push(linked.entry_point_code(0, 0).unwrap().as_slice().to_vec());
+	}
+	assert_ne!(code[0], code[1]);
+
+	assert!(
+		program
+			.specialize(&[slang::SpecializationArg::Expr("float")])
+			.is_err()
+	);
+}
+
+#[test]
+fn conformance_table() {
+	let global_session = slang::GlobalSession::new().unwrap();
+	let session = obtain_test_session(&global_session, &["shaders"]).unwrap();
+	let module = session
+		.load_module_from_source_string(
+			"shapes",
+			"shapes.slang",
+			r#"
+interface IShape {
+	float area();
+};
+
+struct Square : IShape {
+	float side;
+	float area() { return side * side; }
+};
+
+struct Circle : IShape {
+	float radius;
+	float area() { return 3.14159 * radius * radius; }
+};
+
+namespace unit {
+	struct Circle : IShape {
+		float area() { return 3.14159; }
+	};
+}
+
+RWStructuredBuffer<float> output;
+
+[shader("compute")]
+[numthreads(1, 1, 1)]
+void main(uint3 id : SV_DispatchThreadID) {
+	Square square = { float(id.x) };
+	output[id.x] = square.area();
+}
+"#,
+		)
+		.unwrap();
+	let entry_point = module.find_entry_point_by_name("main").unwrap();
+	let program = session
+		.create_composite_component_type(&[
+			module.downcast().clone(),
+			entry_point.downcast().clone(),
+		])
+		.unwrap();
+
+	let layout = program.layout(0).unwrap();
+	let shape = layout.find_type_by_name("IShape").unwrap();
+	let square = layout.find_type_by_name("Square").unwrap();
+	let circle = layout.find_type_by_name("Circle").unwrap();
+	let unit_circle = layout.find_type_by_name("unit.Circle").unwrap();
+
+	let table = session
+		.create_conformance_table(layout, shape, &[circle, square, unit_circle])
+		.unwrap();
+	assert_eq!(table.id("Circle"), Some(0));
+	assert_eq!(table.id("Square"), Some(1));
+	assert_eq!(table.id("unit.Circle"), Some(2));
+	assert!(
+		!session
+			.type_conformance_witness_mangled_name(square, shape)
+			.unwrap()
+			.is_empty()
+	);
+
+	let float = layout.find_type_by_name("float").unwrap();
+	assert!(
+		session
+			.create_conformance_table(layout, shape, &[square, float])
+			.is_err()
+	);
+
+	let linked = session
+		.create_composite_component_type(&[program.value.clone(), table.component.clone()])
+		.unwrap()
+		.link()
+		.unwrap();
+	assert!(!linked.entry_point_code(0, 0).unwrap().as_slice().is_empty());
+}
@@ -0,0 +1,362 @@
+//! Compiling shader permutations selected by preprocessor defines.
+//!
+//! ```ignore
+//! let variants = VariantCompiler::new("lighting")
+//!     .target(CompileTarget::Spirv, Some("spirv_1_5"))
+//!     .search_path("shaders")
+//!     .axis("USE_SHADOWS", ["0", "1"])
+//!     .axis("LIGHT_COUNT", ["1", "4", "8"])
+//!     .compile()?;
+//!
+//! let variant = variants.get(&[("USE_SHADOWS", "1"), ("LIGHT_COUNT", "4")]).unwrap();
+//! let spirv = &variant.code[0][0];
+//! ```
+
+use crate::reflection::snapshot;
+use crate::{CompileTarget, CompilerOptions, Downcast, Error, GlobalSession, SessionConfig};
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use thiserror::Error;
+
+/// One value for each define axis, in the order the axes were added.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DefineSet(pub Vec<(String, String)>);
+
+impl DefineSet {
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.0
+			.iter()
+			.find(|(n, _)| n == name)
+			.map(|(_, v)| v.as_str())
+	}
+}
+
+impl fmt::Display for DefineSet {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, (name, value)) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str(" ")?;
+			}
+			write!(f, "{name}={value}")?;
+		}
+		Ok(())
+	}
+}
+
+/// The output of one or more define sets that compiled to identical results.
+///
+/// Everything is copied out of Slang, so variants can be moved between threads and outlive the sessions that produced
+/// them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variant {
+	/// Hash of the code of all entry points and targets.
+	pub hash: u64,
+	/// Names of the compiled entry points, in the order used by `code`.
+	pub entry_points: Vec<String>,
+	/// Indexed by entry point, then by target.
+	pub code: Vec<Vec<Vec<u8>>>,
+	/// Reflection data, one per target.
+	pub reflection: Vec<snapshot::Shader>,
+}
+
+/// A failed permutation. `defines` is empty for errors that do not belong to one define set, such as a duplicate axis.
+#[derive(Debug, Error)]
+#[error("variant `{defines}`: {error}")]
+pub struct VariantError {
+	pub defines: DefineSet,
+	pub error: Error,
+}
+
+/// The compiled permutations, with identical outputs stored once.
+#[derive(Debug, Clone, Default)]
+pub struct Variants {
+	/// Axis names, in the order used by every [`DefineSet`].
+	axes: Vec<String>,
+	variants: Vec<Variant>,
+	lookup: HashMap<DefineSet, usize>,
+}
+
+impl Variants {
+	/// Looks up the variant for a define set. Defines can be given in any order, but every axis must be present
+	/// exactly once.
+	pub fn get(&self, defines: &[(&str, &str)]) -> Option<&Variant> {
+		if defines.len() != self.axes.len() {
+			return None;
+		}
+
+		let set = self
+			.axes
+			.iter()
+			.map(|axis| {
+				let mut values = defines.iter().filter(|(name, _)| name == axis);
+				match (values.next(), values.next()) {
+					(Some((_, value)), None) => Some((axis.clone(), value.to_string())),
+					_ => None,
+				}
+			})
+			.collect::<Option<Vec<_>>>()?;
+		self.get_by_set(&DefineSet(set))
+	}
+
+	pub fn get_by_set(&self, defines: &DefineSet) -> Option<&Variant> {
+		self.lookup.get(defines).map(|&index| &self.variants[index])
+	}
+
+	/// The unique variants.
+	pub fn unique(&self) -> &[Variant] {
+		&self.variants
+	}
+
+	/// Every compiled define set with its variant.
+	pub fn iter(&self) -> impl Iterator<Item = (&DefineSet, &Variant)> {
+		self.lookup
+			.iter()
+			.map(|(set, &index)| (set, &self.variants[index]))
+	}
+
+	/// Number of compiled define sets.
+	pub fn len(&self) -> usize {
+		self.lookup.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.lookup.is_empty()
+	}
+}
+
+type OptionsFn = dyn Fn() -> CompilerOptions + Sync;
+type FilterFn = dyn Fn(&DefineSet) -> bool + Sync;
+
+/// Compiles every combination of a set of define axes and deduplicates identical outputs.
+///
+/// Every permutation is compiled in its own session, since defines apply to a whole session. Permutations are spread
+/// over worker threads, each with its own global session.
+pub struct VariantCompiler {
+	module: String,
+	entry_points: Vec<String>,
+	session: SessionConfig,
+	axes: Vec<(String, Vec<String>)>,
+	options: Box<OptionsFn>,
+	filter: Option<Box<FilterFn>>,
+	threads: usize,
+}
+
+impl VariantCompiler {
+	/// Creates a compiler for the module `module`, which is loaded through the search paths.
+	pub fn new(module: &str) -> Self {
+		Self {
+			module: module.to_string(),
+			entry_points: Vec::new(),
+			session: SessionConfig::default(),
+			axes: Vec::new(),
+			options: Box::new(CompilerOptions::default),
+			filter: None,
+			threads: std::thread::available_parallelism().map_or(1, |n| n.get()),
+		}
+	}
+
+	/// Adds an entry point to compile. All entry points marked with `[shader(...)]` are compiled if none are added.
+	pub fn entry_point(mut self, name: &str) -> Self {
+		self.entry_points.push(name.to_string());
+		self
+	}
+
+	/// Adds a target with an optional profile name such as `spirv_1_5` or `sm_6_5`.
+	pub fn target(mut self, format: CompileTarget, profile: Option<&str>) -> Self {
+		self.session = self.session.target(format, profile);
+		self
+	}
+
+	pub fn search_path(mut self, path: impl Into<PathBuf>) -> Self {
+		self.session = self.session.search_path(path);
+		self
+	}
+
+	/// Adds a define that takes each of `values` in turn. [`compile`](VariantCompiler::compile) fails if an axis with
+	/// the same name was already added.
+	pub fn axis<V: ToString>(mut self, name: &str, values: impl IntoIterator<Item = V>) -> Self {
+		let values = values.into_iter().map(|v| v.to_string()).collect();
+		self.axes.push((name.to_string(), values));
+		self
+	}
+
+	/// Sets the base options of every session. The function is called once per permutation, possibly from several
+	/// threads, since compiler options cannot be shared between threads.
+	pub fn options(mut self, options: impl Fn() -> CompilerOptions + Sync + 'static) -> Self {
+		self.options = Box::new(options);
+		self
+	}
+
+	/// Only compiles the define sets for which `filter` returns `true`.
+	pub fn filter(mut self, filter: impl Fn(&DefineSet) -> bool + Sync + 'static) -> Self {
+		self.filter = Some(Box::new(filter));
+		self
+	}
+
+	/// Sets the number of worker threads, which defaults to the available parallelism.
+	pub fn threads(mut self, threads: usize) -> Self {
+		self.threads = threads.max(1);
+		self
+	}
+
+	/// The define sets that will be compiled: the cartesian product of all axes, minus filtered ones.
+	pub fn define_sets(&self) -> Vec<DefineSet> {
+		let mut sets = vec![DefineSet(Vec::new())];
+		for (name, values) in &self.axes {
+			sets = sets
+				.into_iter()
+				.flat_map(|set| {
+					values.iter().map(move |value| {
+						let mut set = set.clone();
+						set.0.push((name.clone(), value.clone()));
+						set
+					})
+				})
+				.collect();
+		}
+
+		if let Some(filter) = &self.filter {
+			sets.retain(|set| filter(set));
+		}
+		sets
+	}
+
+	/// Compiles all define sets. Fails with the first failing define set, in the order of [`define_sets`].
+	///
+	/// [`define_sets`]: VariantCompiler::define_sets
+	pub fn compile(&self) -> Result<Variants, VariantError> {
+		for (index, (name, _)) in self.axes.iter().enumerate() {
+			if self.axes[..index].iter().any(|(n, _)| n == name) {
+				return Err(VariantError {
+					defines: DefineSet::default(),
+					error: Error::message(format!("define axis `{name}` is added twice")),
+				});
+			}
+		}
+
+		let sets = self.define_sets();
+		let results = Mutex::new((0..sets.len()).map(|_| None).collect::<Vec<_>>());
+		let next = AtomicUsize::new(0);
+
+		std::thread::scope(|scope| {
+			for _ in 0..self.threads.min(sets.len()) {
+				scope.spawn(|| {
+					let global_session = GlobalSession::new();
+					loop {
+						let index = next.fetch_add(1, Ordering::Relaxed);
+						let Some(set) = sets.get(index) else {
+							break;
+						};
+
+						let result = match &global_session {
+							Some(global_session) => self.compile_one(global_session, set),
+							None => Err(Error::Code(crate::E_FAIL)),
+						};
+						results.lock().unwrap()[index] = Some(result);
+					}
+				});
+			}
+		});
+
+		let mut variants = Variants {
+			axes: self.axes.iter().map(|(name, _)| name.clone()).collect(),
+			..Variants::default()
+		};
+		let mut by_hash = HashMap::<u64, Vec<usize>>::new();
+		for (set, result) in sets.into_iter().zip(results.into_inner().unwrap()) {
+			let variant = match result.expect("every define set should be compiled") {
+				Ok(variant) => variant,
+				Err(error) => {
+					return Err(VariantError {
+						defines: set,
+						error,
+					});
+				}
+			};
+
+			// Equal hashes are compared in full, so a collision cannot merge different variants.
+			let candidates = by_hash.entry(variant.hash).or_default();
+			let index = match candidates
+				.iter()
+				.find(|&&i| variants.variants[i] == variant)
+			{
+				Some(&index) => index,
+				None => {
+					variants.variants.push(variant);
+					candidates.push(variants.variants.len() - 1);
+					variants.variants.len() - 1
+				}
+			};
+			variants.lookup.insert(set, index);
+		}
+		Ok(variants)
+	}
+
+	fn compile_one(
+		&self,
+		global_session: &GlobalSession,
+		defines: &DefineSet,
+	) -> crate::Result<Variant> {
+		let mut options = (self.options)();
+		for (name, value) in &defines.0 {
+			options = options.macro_define(name, value)?;
+		}
+
+		let session = self.session.create_session(global_session, &options)?;
+
+		let module = session.load_module(&self.module)?;
+		let entry_points = if self.entry_points.is_empty() {
+			module.entry_points().collect::<Vec<_>>()
+		} else {
+			self.entry_points
+				.iter()
+				.map(|name| {
+					module
+						.find_entry_point_by_name(name)
+						.ok_or_else(|| Error::missing_entry_point(&self.module, name))
+				})
+				.collect::<crate::Result<Vec<_>>>()?
+		};
+
+		let mut components = vec![module.downcast().clone()];
+		components.extend(entry_points.iter().map(|e| e.downcast().clone()));
+		let program = session.create_composite_component_type(&components)?;
+		let linked = program.link()?;
+
+		let mut hasher = DefaultHasher::new();
+		let mut code = Vec::with_capacity(entry_points.len());
+		for index in 0..entry_points.len() {
+			let mut per_target = Vec::with_capacity(self.session.target_count());
+			for target in 0..self.session.target_count() {
+				let blob = linked.entry_point_code(index as i64, target as i64)?;
+				blob.as_slice().hash(&mut hasher);
+				per_target.push(blob.as_slice().to_vec());
+			}
+			code.push(per_target);
+		}
+
+		let reflection = (0..self.session.target_count())
+			.map(|target| Ok(linked.layout(target as i64)?.snapshot()))
+			.collect::<crate::Result<Vec<_>>>()?;
+
+		Ok(Variant {
+			hash: hasher.finish(),
+			entry_points: entry_points
+				.iter()
+				.map(|e| {
+					e.function_reflection()
+						.name()
+						.unwrap_or_default()
+						.to_string()
+				})
+				.collect(),
+			code,
+			reflection,
+		})
+	}
+}
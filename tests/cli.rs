@@ -0,0 +1,58 @@
+#![cfg(feature = "cli")]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn slang_rs(args: &[&str]) -> Output {
+	Command::new(env!("CARGO_BIN_EXE_slang-rs"))
+		.current_dir(env!("CARGO_MANIFEST_DIR"))
+		.args(args)
+		.output()
+		.unwrap()
+}
+
+#[test]
+fn cli_compile() {
+	let dir = std::env::temp_dir().join(format!("slang-rs-compile-{}", std::process::id()));
+	std::fs::create_dir_all(&dir).unwrap();
+	let output = dir.join("test.spv");
+
+	let result = slang_rs(&[
+		"compile",
+		"shaders/test.slang",
+		"--target",
+		"SPIRV:glsl_450",
+		"--output",
+		&output.to_string_lossy(),
+	]);
+	assert!(result.status.success(), "{result:?}");
+	let code = std::fs::read(&output).unwrap();
+	assert_eq!(&code[..4], &0x07230203u32.to_le_bytes());
+
+	let result = slang_rs(&[
+		"compile",
+		"shaders/test.slang",
+		"--target",
+		"spirv-1.5",
+		"--output",
+		&output.to_string_lossy(),
+	]);
+	assert!(!result.status.success());
+	assert!(String::from_utf8_lossy(&result.stderr).contains("unknown target `spirv-1.5`"));
+
+	std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn cli_deps() {
+	let result = slang_rs(&["deps", "shaders/test.slang"]);
+	assert!(result.status.success(), "{result:?}");
+
+	let stdout = String::from_utf8(result.stdout).unwrap();
+	assert!(
+		stdout
+			.lines()
+			.any(|line| Path::new(line).ends_with("test.slang")),
+		"{stdout}"
+	);
+}
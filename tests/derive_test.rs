@@ -134,3 +134,94 @@ void main(uint3 id : SV_DispatchThreadID) {}
 	let range_enum_extracted: ShaderAttribute = intensity_var.extract_attribute(0).unwrap();
 	assert!(matches!(range_enum_extracted, ShaderAttribute::Range(_)));
 }
+
+#[test]
+fn derive_shader_layout() {
+	use shader_slang::reflection::{LayoutMismatchKind, check_variable_layout};
+
+	#[derive(shader_slang::ShaderLayout)]
+	#[repr(C)]
+	struct Light {
+		position: [f32; 3],
+		intensity: f32,
+		#[slang(name = "colorTint")]
+		color_tint: [f32; 4],
+	}
+
+	#[derive(shader_slang::ShaderLayout)]
+	#[repr(C)]
+	struct StaleLight {
+		position: [f32; 4],
+		intensity: i32,
+		range: f32,
+	}
+
+	let shader_source = r#"
+struct Light {
+    float3 position;
+    float intensity;
+    float4 colorTint;
+};
+
+ConstantBuffer<Light> light;
+"#;
+
+	let global_session = shader_slang::GlobalSession::new().unwrap();
+	let target_desc = shader_slang::TargetDesc::default()
+		.format(shader_slang::CompileTarget::Spirv)
+		.profile(global_session.find_profile("glsl_450").unwrap());
+	let targets = [target_desc];
+	let session_desc = shader_slang::SessionDesc::default().targets(&targets);
+	let session = global_session.create_session(&session_desc).unwrap();
+	let module = session
+		.load_module_from_source_string("layout_test", "layout_test.slang", shader_source)
+		.unwrap();
+	let linked = module.downcast().link().unwrap();
+	let reflection = linked.layout(0).unwrap();
+	let light = reflection.parameter_by_index(0).unwrap();
+
+	let mismatches = check_variable_layout::<Light>(light);
+	assert!(mismatches.is_empty(), "{mismatches:#?}");
+
+	let mismatches = check_variable_layout::<StaleLight>(light)
+		.into_iter()
+		.map(|m| (m.path, m.kind))
+		.collect::<Vec<_>>();
+	assert_eq!(
+		mismatches,
+		[
+			(
+				"position".to_string(),
+				LayoutMismatchKind::Size {
+					host: 16,
+					shader: 12
+				}
+			),
+			(
+				"position".to_string(),
+				LayoutMismatchKind::Shape {
+					host_rows: 1,
+					host_columns: 4,
+					shader_rows: 1,
+					shader_columns: 3
+				}
+			),
+			(
+				"intensity".to_string(),
+				LayoutMismatchKind::Offset {
+					host: 16,
+					shader: 12
+				}
+			),
+			(
+				"intensity".to_string(),
+				LayoutMismatchKind::ScalarType {
+					host: shader_slang::ScalarType::Int32,
+					shader: shader_slang::ScalarType::Float32
+				}
+			),
+			("colorTint".to_string(), LayoutMismatchKind::MissingField),
+			("range".to_string(), LayoutMismatchKind::UnknownField),
+		]
+	);
+}